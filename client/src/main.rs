@@ -1,15 +1,30 @@
 use macroquad::prelude::*;
 use ::rand::Rng;
-use match3_protocol::{ClientMessage, ServerMessage, GameResult};
+use ::rand::{SeedableRng, rngs::StdRng};
+use match3_protocol::{ClientMessage, ServerMessage, GameResult, GameId, MatchRecord, PROTOCOL_VERSION};
 use tokio::sync::mpsc::{unbounded_channel, UnboundedSender, UnboundedReceiver};
+use tokio::sync::mpsc::error::TryRecvError;
 use futures::{StreamExt, SinkExt};
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tokio_tungstenite::tungstenite::Message;
+use std::sync::Arc;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use sha2::Digest;
+use serde::{Serialize, Deserialize};
+
+#[cfg(feature = "udp-transport")]
+mod udp_transport;
 
 const GRID_SIZE: usize = 8;
 const GEM_SIZE: f32 = 60.0;
 const BOARD_OFFSET_X: f32 = 50.0;
 const BOARD_OFFSET_Y: f32 = 150.0;
 const GAME_DURATION: f32 = 90.0;
+const CLIENT_BUILD: &str = env!("CARGO_PKG_VERSION");
+/// Where `Game::save_replay`/`load_replay` read and write the last saved
+/// `ReplayLog`. One fixed slot, same spirit as the root crate's single
+/// local save file - no save browser, just "the last one you kept".
+const REPLAY_FILE_PATH: &str = "last_replay.bin";
 
 // Extended gem types for Brick City Wars
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -29,8 +44,9 @@ enum GemType {
 }
 
 impl GemType {
-    fn random_basic() -> Self {
-        let mut rng = ::rand::thread_rng();
+    /// Draws a basic gem color from `rng`. Every caller shares one `StdRng`
+    /// per match (see `Game::rng`) so both peers draw the same sequence.
+    fn random_basic(rng: &mut StdRng) -> Self {
         match rng.gen_range(0..6) {
             0 => GemType::Red,
             1 => GemType::Blue,
@@ -56,6 +72,38 @@ impl GemType {
         matches!(self, GemType::Garbage)
     }
 
+    /// Encodes a cell for `BoardUpdate`/`BoardSnapshot` wire transfer.
+    fn to_wire_id(&self) -> u8 {
+        match self {
+            GemType::Red => 0,
+            GemType::Blue => 1,
+            GemType::Green => 2,
+            GemType::Yellow => 3,
+            GemType::Purple => 4,
+            GemType::Orange => 5,
+            GemType::Drill => 6,
+            GemType::Barrel => 7,
+            GemType::Mixer => 8,
+            GemType::Garbage => 9,
+        }
+    }
+
+    fn from_wire_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(GemType::Red),
+            1 => Some(GemType::Blue),
+            2 => Some(GemType::Green),
+            3 => Some(GemType::Yellow),
+            4 => Some(GemType::Purple),
+            5 => Some(GemType::Orange),
+            6 => Some(GemType::Drill),
+            7 => Some(GemType::Barrel),
+            8 => Some(GemType::Mixer),
+            9 => Some(GemType::Garbage),
+            _ => None,
+        }
+    }
+
     fn color(&self) -> Color {
         match self {
             GemType::Red => Color::from_rgba(255, 50, 50, 255),
@@ -114,6 +162,73 @@ impl BoosterType {
     }
 }
 
+// Bounded taunt/emote channel players can send during a match instead of
+// free text.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Emote {
+    Gg,
+    Nice,
+    Oops,
+    Angry,
+}
+
+impl Emote {
+    /// All variants, in the order the on-screen emote bar (and its F1-F4
+    /// hotkeys) lists them.
+    const ALL: [Emote; 4] = [Emote::Gg, Emote::Nice, Emote::Oops, Emote::Angry];
+
+    fn id(&self) -> u8 {
+        match self {
+            Emote::Gg => 0,
+            Emote::Nice => 1,
+            Emote::Oops => 2,
+            Emote::Angry => 3,
+        }
+    }
+
+    fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(Emote::Gg),
+            1 => Some(Emote::Nice),
+            2 => Some(Emote::Oops),
+            3 => Some(Emote::Angry),
+            _ => None,
+        }
+    }
+
+    fn label(&self) -> &str {
+        match self {
+            Emote::Gg => "GG",
+            Emote::Nice => "Nice!",
+            Emote::Oops => "Oops...",
+            Emote::Angry => "Grr!",
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            Emote::Gg => Color::from_rgba(100, 255, 150, 255),
+            Emote::Nice => Color::from_rgba(100, 200, 255, 255),
+            Emote::Oops => Color::from_rgba(255, 200, 100, 255),
+            Emote::Angry => Color::from_rgba(255, 80, 80, 255),
+        }
+    }
+}
+
+const EMOTE_BUBBLE_DURATION: f32 = 2.0;
+const RECONNECT_INITIAL_BACKOFF: f32 = 1.0;
+const RECONNECT_MAX_BACKOFF: f32 = 16.0;
+// How long an online player can go without a click, booster, or keypress
+// before we forfeit on their behalf; inbound server messages do not reset
+// this clock. Offline play never checks this - there's no opponent to leave
+// waiting.
+const AFK_TIMEOUT: f64 = 60.0;
+// How often to re-poll while sitting on the Leaderboard screen. Each poll
+// sends the cached version, so a server with nothing new just answers
+// LeaderboardUnchanged/re-echoes the same match list instead of a full
+// re-sort and re-transmit.
+const LEADERBOARD_POLL_INTERVAL: f32 = 5.0;
+
 #[derive(Clone, Copy)]
 struct Booster {
     booster_type: BoosterType,
@@ -155,12 +270,25 @@ impl Gem {
 #[derive(PartialEq, Clone)]
 enum GameState {
     Menu,
+    // Picking which known server to dial, with live player/queue counts
+    // fetched via a status-only ping (see `fetch_server_status`).
+    ServerBrowser,
     Login,
     Connecting,
     WaitingForMatch,
     Playing,
     GameOver,
     Leaderboard,
+    // The socket dropped while online and mid-match; retrying the connection
+    // with backoff before giving up and falling through to GameOver.
+    Reconnecting,
+    // Watching another in-progress game (usually one's own match after an
+    // early loss) via `BoardSnapshot` broadcasts; read-only, no own grid.
+    Spectating,
+    // Feeding a loaded `ReplayLog` back through the same
+    // swap_gems/activate_special/activate_booster path a live match uses,
+    // reconstructing it frame-for-frame. See `Game::replay_playback`.
+    Replay,
 }
 
 #[derive(PartialEq, Clone, Copy)]
@@ -169,20 +297,362 @@ enum NetworkMode {
     Online,     // Real multiplayer
 }
 
+/// Transport-layer failures the socket task can hit. These never reach the
+/// wire (they describe the absence or breakdown of a connection, not
+/// something a server would send), so they travel over `NetworkBridge`'s own
+/// `errors` channel rather than as a `ServerMessage` variant.
+#[derive(Debug, Clone, thiserror::Error)]
+enum NetError {
+    #[error("failed to connect to server: {0}")]
+    ConnectFailed(String),
+    #[error("connection closed")]
+    Closed,
+    #[error("failed to serialize outgoing message: {0}")]
+    Serialize(String),
+    #[error("outgoing message queue is full")]
+    SendQueueFull,
+    #[error("timed out waiting for authentication")]
+    AuthTimeout,
+}
+
 // Network bridge for async WebSocket communication
 struct NetworkBridge {
     to_server: UnboundedSender<ClientMessage>,
     from_server: UnboundedReceiver<ServerMessage>,
+    errors: UnboundedReceiver<NetError>,
 }
 
 impl NetworkBridge {
-    fn send(&self, msg: ClientMessage) {
-        let _ = self.to_server.send(msg);
+    /// Queues `msg` for the socket task. Fails only if that task has already
+    /// torn down its receiver, i.e. the connection is already dead - callers
+    /// should route the error into the same place they'd handle a detected
+    /// disconnect rather than retry inline.
+    fn send(&self, msg: ClientMessage) -> Result<(), NetError> {
+        self.to_server.send(msg).map_err(|_| NetError::Closed)
     }
 
     fn try_recv(&mut self) -> Option<ServerMessage> {
         self.from_server.try_recv().ok()
     }
+
+    /// Drains one pending transport failure reported by the socket task, if any.
+    fn try_recv_error(&mut self) -> Option<NetError> {
+        self.errors.try_recv().ok()
+    }
+
+    /// True once the network task's sender half has been dropped, i.e. the
+    /// WebSocket connection closed (cleanly or otherwise).
+    fn is_disconnected(&mut self) -> bool {
+        matches!(self.from_server.try_recv(), Err(TryRecvError::Disconnected))
+    }
+}
+
+/// One player action the shared online simulation needs to agree on.
+/// Serialized with `bincode` (not the outer message's JSON) inside
+/// `ClientMessage::InputFrame`/`ServerMessage::OpponentInputFrame`, so the
+/// per-frame payload stays compact regardless of how many inputs land in a
+/// given frame.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+enum PlayerInput {
+    Swap { row1: usize, col1: usize, row2: usize, col2: usize },
+    ActivateSpecial { row: usize, col: usize },
+    ActivateBooster { booster_index: usize },
+}
+
+/// How many frames ahead of `current_frame` a freshly queued input is
+/// scheduled for. Gives the round trip to the opponent (and back) room to
+/// land before that frame is due, hiding ordinary latency without the
+/// simulation having to stall.
+const INPUT_DELAY_FRAMES: u32 = 3;
+
+/// How often (in `match_frame`s) each online side hashes its board and
+/// exchanges the result via `ClientMessage::StateChecksum`/
+/// `OpponentStateChecksum`, to catch the two simulations drifting apart.
+const CHECKSUM_INTERVAL_FRAMES: u32 = 30;
+/// How many of the local side's own past checksums `Game::own_checksums`
+/// keeps, so a same-or-later opponent checksum for an older frame can still
+/// be matched even if it arrives out of order.
+const CHECKSUM_HISTORY_CAP: usize = 32;
+
+/// How many lines `Game::chat_log` keeps before dropping the oldest.
+const CHAT_LOG_MAX_LINES: usize = 8;
+
+/// Frame-buffered input exchange for online play: each side queues its own
+/// inputs, schedules them `INPUT_DELAY_FRAMES` out, and a frame only
+/// resolves once both players' inputs for it have arrived - see
+/// `Game::tick_lockstep`. This is additive alongside the existing
+/// per-event streaming (`SwapGems`/`ActivateSpecial`/...), which still
+/// drives the live board this commit; splicing `tick_lockstep`'s resolved
+/// frames into `check_and_remove_matches`/gravity as the sole mutation path
+/// - and dropping the streamed messages - is follow-up work once this
+/// buffering layer has proven itself in practice.
+struct LockstepState {
+    /// Next frame the shared simulation is waiting to resolve.
+    current_frame: u32,
+    /// Inputs captured locally since the last tick, not yet scheduled/sent.
+    pending_local: Vec<PlayerInput>,
+    /// Sent to the opponent already, keyed by the frame they're scheduled for.
+    scheduled_local: HashMap<u32, Vec<PlayerInput>>,
+    /// Received from the opponent, keyed by the frame they're scheduled for.
+    scheduled_remote: HashMap<u32, Vec<PlayerInput>>,
+}
+
+impl LockstepState {
+    fn new() -> Self {
+        Self {
+            current_frame: 0,
+            pending_local: Vec::new(),
+            scheduled_local: HashMap::new(),
+            scheduled_remote: HashMap::new(),
+        }
+    }
+
+    fn queue_input(&mut self, input: PlayerInput) {
+        self.pending_local.push(input);
+    }
+
+    fn receive_remote(&mut self, frame: u32, inputs: Vec<PlayerInput>) {
+        self.scheduled_remote.entry(frame).or_insert_with(Vec::new).extend(inputs);
+    }
+}
+
+/// Every `PlayerInput` captured on one match frame, as saved to / loaded
+/// from a replay file. Reuses `PlayerInput` itself (see `LockstepState`)
+/// since the set of recordable actions is identical.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ReplayFrame {
+    frame: u32,
+    inputs: Vec<PlayerInput>,
+}
+
+/// A recorded match: the board seed plus every local input, frame-indexed.
+/// `bincode`-serializable so it round-trips to a file byte-for-byte.
+/// Replaying `frames` through `swap_gems`/`activate_special`/`activate_booster`
+/// in order, starting from a `StdRng` seeded with `seed`, reconstructs the
+/// exact same grid and score, since those methods (and the RNG they draw
+/// from) are otherwise driven only by the input stream itself - this is
+/// also what makes the log useful as a regression check: replay it and
+/// assert the final `score` matches.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ReplayLog {
+    seed: u64,
+    frames: Vec<ReplayFrame>,
+}
+
+/// A loaded `ReplayLog` plus a cursor into it, while `GameState::Replay` is
+/// feeding its frames back through the normal input-handling path.
+struct ReplayPlayback {
+    log: ReplayLog,
+    next_frame_index: usize,
+}
+
+/// Variant-specific data for a [`Particle`]; shared fields (position,
+/// velocity, lifetime) live on `Particle` itself since every kind animates
+/// and fades the same way.
+enum ParticleKind {
+    /// A gem-shatter spark flung outward from a removed cell.
+    Spark { color: Color },
+    /// A drill beam sweeping the cleared row.
+    DrillBeamRow(usize),
+    /// A drill beam sweeping the cleared column.
+    DrillBeamCol(usize),
+    /// An expanding ring for a barrel/mixer detonation.
+    Explosion { color: Color },
+    /// Floating score or "-N Incoming Blocked!" text drifting upward.
+    FloatingText { text: String, color: Color },
+}
+
+/// A purely cosmetic animated effect, grid-relative to whichever board it
+/// was spawned against. Spawned from special activations and the removal
+/// loop in `resolve_match_wave` so those events read as on-screen effects
+/// instead of only a `println!`; never touches game state.
+struct Particle {
+    x: f32,
+    y: f32,
+    vx: f32,
+    vy: f32,
+    lifetime: f32,
+    max_lifetime: f32,
+    anim_counter: f32,
+    kind: ParticleKind,
+}
+
+impl Particle {
+    const SPARK_LIFETIME: f32 = 0.4;
+    const BEAM_LIFETIME: f32 = 0.25;
+    const EXPLOSION_LIFETIME: f32 = 0.35;
+    const TEXT_LIFETIME: f32 = 0.8;
+
+    fn spark(x: f32, y: f32, color: Color, rng: &mut StdRng) -> Self {
+        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+        let speed = rng.gen_range(80.0..220.0);
+        Self {
+            x,
+            y,
+            vx: angle.cos() * speed,
+            vy: angle.sin() * speed,
+            lifetime: Self::SPARK_LIFETIME,
+            max_lifetime: Self::SPARK_LIFETIME,
+            anim_counter: 0.0,
+            kind: ParticleKind::Spark { color },
+        }
+    }
+
+    fn drill_beam_row(row: usize) -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            vx: 0.0,
+            vy: 0.0,
+            lifetime: Self::BEAM_LIFETIME,
+            max_lifetime: Self::BEAM_LIFETIME,
+            anim_counter: 0.0,
+            kind: ParticleKind::DrillBeamRow(row),
+        }
+    }
+
+    fn drill_beam_col(col: usize) -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            vx: 0.0,
+            vy: 0.0,
+            lifetime: Self::BEAM_LIFETIME,
+            max_lifetime: Self::BEAM_LIFETIME,
+            anim_counter: 0.0,
+            kind: ParticleKind::DrillBeamCol(col),
+        }
+    }
+
+    fn explosion(x: f32, y: f32, color: Color) -> Self {
+        Self {
+            x,
+            y,
+            vx: 0.0,
+            vy: 0.0,
+            lifetime: Self::EXPLOSION_LIFETIME,
+            max_lifetime: Self::EXPLOSION_LIFETIME,
+            anim_counter: 0.0,
+            kind: ParticleKind::Explosion { color },
+        }
+    }
+
+    fn floating_text(x: f32, y: f32, text: String, color: Color) -> Self {
+        Self {
+            x,
+            y,
+            vx: 0.0,
+            vy: -40.0,
+            lifetime: Self::TEXT_LIFETIME,
+            max_lifetime: Self::TEXT_LIFETIME,
+            anim_counter: 0.0,
+            kind: ParticleKind::FloatingText { text, color },
+        }
+    }
+
+    fn tick(&mut self, dt: f32) {
+        self.x += self.vx * dt;
+        self.y += self.vy * dt;
+        self.lifetime -= dt;
+        self.anim_counter += dt;
+    }
+
+    fn is_dead(&self) -> bool {
+        self.lifetime <= 0.0
+    }
+
+    /// Fades linearly from fully opaque to transparent over its lifetime.
+    fn alpha(&self) -> f32 {
+        (self.lifetime / self.max_lifetime).clamp(0.0, 1.0)
+    }
+}
+
+/// Text-entry state for the Login username field: the text plus a byte
+/// offset cursor, always kept on a UTF-8 char boundary so inserts/deletes
+/// never split a multi-byte codepoint.
+struct UsernameEditor {
+    text: String,
+    cursor: usize,
+}
+
+impl UsernameEditor {
+    const MAX_CHARS: usize = 20;
+
+    fn new() -> Self {
+        Self { text: String::new(), cursor: 0 }
+    }
+
+    fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+    }
+
+    fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Byte offset of the char boundary immediately before `cursor`.
+    fn prev_boundary(&self) -> usize {
+        self.text[..self.cursor].char_indices().last().map(|(i, _)| i).unwrap_or(0)
+    }
+
+    /// Byte offset of the char boundary immediately after `cursor`.
+    fn next_boundary(&self) -> usize {
+        self.text[self.cursor..]
+            .char_indices()
+            .nth(1)
+            .map(|(i, _)| self.cursor + i)
+            .unwrap_or(self.text.len())
+    }
+
+    fn insert(&mut self, c: char) {
+        if self.text.chars().count() >= Self::MAX_CHARS {
+            return;
+        }
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    fn backspace(&mut self) {
+        if self.cursor == 0 {
+            return;
+        }
+        let prev = self.prev_boundary();
+        self.text.remove(prev);
+        self.cursor = prev;
+    }
+
+    fn delete_forward(&mut self) {
+        if self.cursor >= self.text.len() {
+            return;
+        }
+        self.text.remove(self.cursor);
+    }
+
+    fn move_left(&mut self) {
+        self.cursor = self.prev_boundary();
+    }
+
+    fn move_right(&mut self) {
+        self.cursor = self.next_boundary();
+    }
+
+    fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    fn move_end(&mut self) {
+        self.cursor = self.text.len();
+    }
+}
+
+/// Which of the Login screen's text fields keyboard input routes to right
+/// now; Tab cycles through them in this order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LoginField {
+    Username,
+    Password,
+    ServerTarget,
 }
 
 struct Game {
@@ -206,20 +676,113 @@ struct Game {
     opponent_requested_rematch: bool, // Whether opponent requested rematch
     disconnect_reason: Option<String>, // Reason for disconnect (if any)
     network_bridge: Option<NetworkBridge>, // WebSocket communication bridge
+    // `get_time()` of the last click, booster activation, or key press.
+    // Checked against `AFK_TIMEOUT` in `Connecting`, `Playing`, and
+    // `GameOver` to auto-forfeit an online match nobody's watching; see
+    // `Game::touch_activity`.
+    last_active_time: f64,
     // User account info
     username: String,          // Player's username
-    pending_username: String,  // Username being typed in login screen
-    elo: i32,                  // Player's ELO rating
+    password: String,          // Submitted value of `pending_password`, sent with Login/Register
+    pending_username: UsernameEditor, // Username being typed in login screen
+    pending_password: UsernameEditor, // Password being typed in login screen; masked on draw
+    // Optional `host:port` or room code typed into the Login screen's second
+    // field, reusing the same cursor-aware text editing as the username.
+    connect_target_input: UsernameEditor,
+    // Parsed, trimmed value of `connect_target_input` applied to
+    // `network_config.url` on Continue; `None` keeps the default server.
+    connect_target: Option<String>,
+    // Which Login text field keyboard input routes to right now.
+    login_focus: LoginField,
+    // If true, the next HelloAck sends `ClientMessage::Register` instead of
+    // `Login` - toggled by the Login screen's "New player? Register" link.
+    register_mode: bool,
+    rating: f64,               // Player's Glicko-2 rating (r)
+    rating_deviation: f64,     // Player's Glicko-2 rating deviation (RD) - confidence in `rating`
     wins: u32,                 // Total wins
     losses: u32,               // Total losses
     bricks: u32,               // Currency: Bricks
     gold: u32,                 // Currency: Gold
-    leaderboard_data: Vec<(String, i32)>, // Leaderboard data (username, elo)
+    leaderboard_data: Vec<(String, f64)>, // Leaderboard data (username, rating)
+    leaderboard_version: u64, // Version `leaderboard_data` reflects; 0 means never fetched
     connecting_for_leaderboard: bool, // Flag to track if connecting for leaderboard
+    // Active matches fetched alongside the leaderboard via `ListMatches`, for
+    // the Leaderboard screen's spectate list: (game_id, player1, player2).
+    match_list: Vec<(GameId, String, String)>,
+    // Counts down to the next re-poll while `state == GameState::Leaderboard`;
+    // see `LEADERBOARD_POLL_INTERVAL`.
+    leaderboard_poll_timer: f32,
+    // Most recently fetched page of our own match history, via
+    // `RequestHistory`; `match_history_cursor` is the `next_cursor` to pass
+    // as `before` to fetch the page after it, `None` once there's no more.
+    match_history: Vec<MatchRecord>,
+    match_history_cursor: Option<i64>,
+    // Most recently fetched `RequestHeadToHead` result: our recent matches
+    // against one specific opponent plus our overall record against them.
+    head_to_head: Vec<MatchRecord>,
+    head_to_head_wins: u32,
+    head_to_head_losses: u32,
+    own_emote: Option<(Emote, f32)>,      // Emote we sent + time left to show it
+    opponent_emote: Option<(Emote, f32)>, // Emote opponent sent + time left to show it
+    session_token: Option<String>, // Issued at AuthAccepted; presented again via Resume
+    reconnect_backoff: f32,        // Current backoff duration between reconnect attempts
+    reconnect_timer: f32,          // Time left before the next reconnect attempt
+    current_game_id: Option<GameId>, // Set from GameStarted; lets us spectate our own match after an early loss
+    network_config: NetworkConfig, // Where to connect and how to trust the server's TLS cert
+    // Opponent's board as last reported by `BoardSnapshot`, rendered at a
+    // mirrored offset during a live match or as the main board while
+    // spectating. `None` until the first snapshot arrives.
+    opponent_grid: Option<Vec<Vec<Option<Gem>>>>,
+    opponent_energy: u32,
+    opponent_name: Option<String>, // Set from MatchFound; shown in the HUD instead of a raw player id
+    opponent_win_probability: Option<f64>, // Set from MatchFound; the matchmaker's pre-match win estimate for us
+    opponent_board_version: u64, // Last BoardSnapshot.version applied; reported back on Resume
+    // Set from `OpponentDisconnected` while the match is still live - the
+    // server pauses the countdown for up to this many seconds waiting for
+    // them to `Resume` before forfeiting the match. Cleared by
+    // `OpponentReconnected` or whenever the match actually ends.
+    opponent_reconnect_grace: Option<u64>,
+    server_list: Vec<ServerBrowserEntry>, // Rows shown on the ServerBrowser screen
+    server_browser_needs_refresh: bool,   // Set on entering ServerBrowser; cleared once statuses are fetched
+    // Single RNG stream for everything board-related (initial fill, cascade
+    // refills, garbage spawning). For online matches it's reseeded from the
+    // server's `GameStarted { seed }` so both clients draw identical boards;
+    // consumption order (row-major fill, then left-to-right per settle step)
+    // is an invariant — reordering it desyncs the two clients and the server
+    // replay checker.
+    rng: StdRng,
+    // Frame-buffered input exchange for online play; see `LockstepState`.
+    lockstep: LockstepState,
+    // Cosmetic effects for gem removal and special detonations; see `Particle`.
+    particles: Vec<Particle>,
+    // Frame-indexed log of this match's (or the last one's) local inputs,
+    // rebuilt from `seed` by `reseed_rng`; see `ReplayLog`.
+    replay_log: ReplayLog,
+    // Frame counter the replay log is keyed against; advances on each tick
+    // of `Playing` or `Replay` whose `animation_timer` has run out (see
+    // `update`).
+    match_frame: u32,
+    // Set while `state == GameState::Replay`; drives the board from a
+    // loaded `ReplayLog` instead of live input. See `Game::tick_replay`.
+    replay_playback: Option<ReplayPlayback>,
+    // This side's own recent `(frame, hash)` checksums, capped at
+    // `CHECKSUM_HISTORY_CAP`; see `Game::send_checksum`/`handle_opponent_checksum`.
+    own_checksums: VecDeque<(u32, u64)>,
+    // Rendered lines for the in-match chat panel, already formatted as
+    // "sender: text"; oldest dropped past `CHAT_LOG_MAX_LINES`.
+    chat_log: Vec<String>,
+    // Text currently being typed into the chat box; only live while
+    // `chat_active` is set.
+    chat_input: String,
+    // Whether the chat box is capturing keyboard input right now. While
+    // true, booster/emote hotkeys are suppressed so typing doesn't
+    // accidentally trigger them.
+    chat_active: bool,
 }
 
 impl Game {
     fn new() -> Self {
+        let seed: u64 = ::rand::random();
         let mut game = Self {
             grid: vec![vec![None; GRID_SIZE]; GRID_SIZE],
             selected: None,
@@ -245,20 +808,96 @@ impl Game {
             opponent_requested_rematch: false,
             disconnect_reason: None,
             network_bridge: None,
+            last_active_time: get_time(),
             username: String::new(),
-            pending_username: String::new(),
-            elo: 1000,
+            password: String::new(),
+            pending_username: UsernameEditor::new(),
+            pending_password: UsernameEditor::new(),
+            connect_target_input: UsernameEditor::new(),
+            connect_target: None,
+            login_focus: LoginField::Username,
+            register_mode: false,
+            rating: 1500.0,
+            rating_deviation: 350.0,
             wins: 0,
             losses: 0,
             bricks: 0,
             gold: 0,
             leaderboard_data: Vec::new(),
+            leaderboard_version: 0,
             connecting_for_leaderboard: false,
+            match_list: Vec::new(),
+            match_history: Vec::new(),
+            match_history_cursor: None,
+            head_to_head: Vec::new(),
+            head_to_head_wins: 0,
+            head_to_head_losses: 0,
+            leaderboard_poll_timer: LEADERBOARD_POLL_INTERVAL,
+            own_emote: None,
+            opponent_emote: None,
+            session_token: None,
+            reconnect_backoff: RECONNECT_INITIAL_BACKOFF,
+            reconnect_timer: 0.0,
+            current_game_id: None,
+            network_config: NetworkConfig::default_server(),
+            opponent_grid: None,
+            opponent_energy: 0,
+            opponent_name: None,
+            opponent_win_probability: None,
+            opponent_board_version: 0,
+            opponent_reconnect_grace: None,
+            server_list: KNOWN_SERVERS
+                .iter()
+                .map(|(name, url)| ServerBrowserEntry {
+                    name: name.to_string(),
+                    url: url.to_string(),
+                    status: ServerStatus::Pending,
+                })
+                .collect(),
+            server_browser_needs_refresh: false,
+            rng: StdRng::seed_from_u64(seed),
+            lockstep: LockstepState::new(),
+            particles: Vec::new(),
+            replay_log: ReplayLog { seed, frames: Vec::new() },
+            match_frame: 0,
+            replay_playback: None,
+            own_checksums: VecDeque::new(),
+            chat_log: Vec::new(),
+            chat_input: String::new(),
+            chat_active: false,
         };
         game.initialize_board();
         game
     }
 
+    /// Reseeds the shared board RNG from `seed` and starts a fresh replay
+    /// recording for the match that seed belongs to. Called whenever a
+    /// match (re)begins, whether from a local coin flip (offline) or a
+    /// seed handed down by the server (`GameStarted`/`RematchAccepted`).
+    fn reseed_rng(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.replay_log = ReplayLog { seed, frames: Vec::new() };
+        self.match_frame = 0;
+        self.own_checksums.clear();
+    }
+
+    /// Appends `input` to the current match frame's `ReplayFrame`, creating
+    /// one if this is the first input recorded this frame. No-ops during
+    /// `GameState::Replay` so played-back inputs don't get re-recorded into
+    /// the log they came from.
+    fn record_input(&mut self, input: PlayerInput) {
+        if self.state == GameState::Replay {
+            return;
+        }
+        match self.replay_log.frames.last_mut() {
+            Some(last) if last.frame == self.match_frame => last.inputs.push(input),
+            _ => self.replay_log.frames.push(ReplayFrame {
+                frame: self.match_frame,
+                inputs: vec![input],
+            }),
+        }
+    }
+
     fn reset_game(&mut self) {
         // Reset game state for rematch
         self.grid = vec![vec![None; GRID_SIZE]; GRID_SIZE];
@@ -277,6 +916,19 @@ impl Game {
         self.requested_rematch = false;
         self.opponent_requested_rematch = false;
         self.disconnect_reason = None;
+        self.own_emote = None;
+        self.opponent_emote = None;
+        self.opponent_grid = None;
+        self.opponent_energy = 0;
+        self.opponent_name = None;
+        self.opponent_board_version = 0;
+        self.opponent_reconnect_grace = None;
+        self.particles.clear();
+        self.replay_playback = None;
+        self.own_checksums.clear();
+        self.chat_log.clear();
+        self.chat_input.clear();
+        self.chat_active = false;
 
         // Reset booster cooldowns
         for booster in &mut self.boosters {
@@ -287,11 +939,55 @@ impl Game {
         self.state = GameState::Playing;
     }
 
+    /// Writes the just-finished offline match's `replay_log` to
+    /// `REPLAY_FILE_PATH`. Failure (e.g. no write permission) is logged and
+    /// otherwise ignored, same as every other local-I/O call in this client.
+    fn save_replay(&self) {
+        match bincode::serialize(&self.replay_log) {
+            Ok(bytes) => {
+                if let Err(e) = std::fs::write(REPLAY_FILE_PATH, bytes) {
+                    println!("Failed to save replay: {}", e);
+                } else {
+                    println!("Replay saved to {}", REPLAY_FILE_PATH);
+                }
+            }
+            Err(e) => println!("Failed to encode replay: {}", e),
+        }
+    }
+
+    /// Loads `REPLAY_FILE_PATH` and starts `GameState::Replay` from it.
+    /// No-ops (with a log message) if the file is missing or corrupt.
+    fn load_replay(&mut self) {
+        let bytes = match std::fs::read(REPLAY_FILE_PATH) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                println!("Failed to load replay: {}", e);
+                return;
+            }
+        };
+        let log = match bincode::deserialize::<ReplayLog>(&bytes) {
+            Ok(log) => log,
+            Err(e) => {
+                println!("Failed to decode replay: {}", e);
+                return;
+            }
+        };
+
+        self.network_mode = NetworkMode::Offline;
+        // Rebuild the board from the log's own seed rather than
+        // `reseed_rng` (which would also reset `replay_log` and start a new
+        // recording over the one we're about to play back).
+        self.rng = StdRng::seed_from_u64(log.seed);
+        self.reset_game();
+        self.replay_playback = Some(ReplayPlayback { log, next_frame_index: 0 });
+        self.state = GameState::Replay;
+    }
+
     fn initialize_board(&mut self) {
         for row in 0..GRID_SIZE {
             for col in 0..GRID_SIZE {
                 loop {
-                    let gem = Gem::new(GemType::random_basic());
+                    let gem = Gem::new(GemType::random_basic(&mut self.rng));
                     self.grid[row][col] = Some(gem);
 
                     if !self.would_create_initial_match(row, col) {
@@ -348,6 +1044,10 @@ impl Game {
             GameState::Connecting
         } else {
             self.network_mode = NetworkMode::Offline;
+            // Online matches reseed from the server's `GameStarted.seed`;
+            // offline ones need their own fresh seed here so this match has
+            // one at all, to record into `replay_log` and reproduce from.
+            self.reseed_rng(::rand::random());
             GameState::Playing
         };
 
@@ -359,18 +1059,160 @@ impl Game {
         self.pending_garbage = 0;
         self.garbage_queue = 0;
         self.garbage_timer = 0.0;
+        self.current_game_id = None;
+        self.opponent_grid = None;
+        self.opponent_energy = 0;
+        self.opponent_name = None;
+        self.opponent_board_version = 0;
         self.initialize_board();
     }
 
     fn set_network_bridge(&mut self, bridge: NetworkBridge) {
-        // Send JoinQueue message immediately after connection
-        bridge.send(ClientMessage::JoinQueue);
+        // The protocol-version handshake must complete before anything else;
+        // Login/Resume follows once HelloAck{accepted: true} arrives (see
+        // handle_server_message).
+        if let Err(err) = bridge.send(ClientMessage::Hello {
+            protocol_version: PROTOCOL_VERSION,
+            client_build: CLIENT_BUILD.to_string(),
+        }) {
+            self.disconnect_reason = Some(format!("Connection problem: {}", err));
+            return;
+        }
         self.network_bridge = Some(bridge);
-        self.state = GameState::WaitingForMatch;
+    }
+
+    /// Sends `msg` over the active bridge, if any. A send failure is routed
+    /// into `handle_transport_error` instead of being swallowed, so the UI
+    /// reacts the same way it would to a detected disconnect.
+    fn send_to_server(&mut self, msg: ClientMessage) {
+        let result = match &self.network_bridge {
+            Some(bridge) => bridge.send(msg),
+            None => return,
+        };
+        if let Err(err) = result {
+            self.handle_transport_error(err);
+        }
+    }
+
+    /// Tears down the dead bridge and, if we were mid-match online, kicks off
+    /// the same backoff-and-retry flow a detected disconnect would.
+    fn handle_transport_error(&mut self, err: NetError) {
+        println!("Network error: {}", err);
+        self.network_bridge = None;
+        self.disconnect_reason = Some(format!("Connection problem: {}", err));
+        if self.network_mode == NetworkMode::Online
+            && matches!(self.state, GameState::Playing | GameState::WaitingForMatch)
+        {
+            self.state = GameState::Reconnecting;
+            self.reconnect_timer = self.reconnect_backoff;
+        }
+    }
+
+    /// Schedules this tick's queued local inputs onto a future frame and
+    /// sends them, then resolves every frame both sides have now fully
+    /// supplied inputs for. Resolving a frame here only advances
+    /// `lockstep.current_frame` and logs the agreed-upon inputs; it doesn't
+    /// (yet) replace the immediate apply + streamed-message path that
+    /// actually mutates the board this commit - see `LockstepState`'s doc
+    /// comment for why that cutover is deliberately follow-up work.
+    fn tick_lockstep(&mut self) {
+        if !self.lockstep.pending_local.is_empty() {
+            let inputs = std::mem::take(&mut self.lockstep.pending_local);
+            let target_frame = self.lockstep.current_frame + INPUT_DELAY_FRAMES;
+            match bincode::serialize(&inputs) {
+                Ok(bytes) => {
+                    self.lockstep.scheduled_local.insert(target_frame, inputs);
+                    self.send_to_server(ClientMessage::InputFrame { frame: target_frame, inputs: bytes });
+                }
+                Err(e) => println!("Failed to encode lockstep input frame: {}", e),
+            }
+        }
+
+        // Resolve every consecutive frame both sides have already fully
+        // supplied - ordinarily at most one per tick, but this catches up
+        // if ticks were skipped.
+        loop {
+            let frame = self.lockstep.current_frame;
+            if !self.lockstep.scheduled_local.contains_key(&frame)
+                || !self.lockstep.scheduled_remote.contains_key(&frame)
+            {
+                break;
+            }
+            let local = self.lockstep.scheduled_local.remove(&frame).unwrap();
+            let remote = self.lockstep.scheduled_remote.remove(&frame).unwrap();
+            if !local.is_empty() || !remote.is_empty() {
+                println!(
+                    "Lockstep frame {} resolved: {} local input(s), {} remote input(s)",
+                    frame, local.len(), remote.len()
+                );
+            }
+            self.lockstep.current_frame += 1;
+        }
+    }
+
+    /// Hashes this side's board (gem types + positions), `garbage_queue`,
+    /// and `score` into a compact fingerprint. Anything not folded into the
+    /// hash is free to differ between clients without being flagged as a
+    /// desync.
+    fn compute_state_checksum(&self) -> u64 {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        encode_board(&self.grid).hash(&mut hasher);
+        self.garbage_queue.hash(&mut hasher);
+        self.score.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Hashes the current board, records it in `own_checksums`, and sends it
+    /// to the opponent. Called every `CHECKSUM_INTERVAL_FRAMES` while an
+    /// online match is playing - see `update`'s `GameState::Playing` arm.
+    fn send_checksum(&mut self) {
+        let hash = self.compute_state_checksum();
+        self.own_checksums.push_back((self.match_frame, hash));
+        if self.own_checksums.len() > CHECKSUM_HISTORY_CAP {
+            self.own_checksums.pop_front();
+        }
+        self.send_to_server(ClientMessage::StateChecksum { frame: self.match_frame, hash });
+    }
+
+    /// Compares an opponent's checksum for `frame` against this side's own
+    /// recorded value for the same frame. There's no way to tell which side
+    /// is "right" from here, so a mismatch simply ends the match with a
+    /// clear reason instead of letting the two boards silently keep
+    /// drifting apart. A frame not found in `own_checksums` (evicted, or
+    /// not reached locally yet) is silently ignored rather than treated as
+    /// a mismatch.
+    fn handle_opponent_checksum(&mut self, frame: u32, opponent_hash: u64) {
+        if let Some(&(_, own_hash)) = self.own_checksums.iter().find(|&&(f, _)| f == frame) {
+            if own_hash != opponent_hash {
+                println!(
+                    "Desync detected at frame {}: local checksum {:#x} vs opponent {:#x}",
+                    frame, own_hash, opponent_hash
+                );
+                self.disconnect_reason = Some(format!("Desync detected at frame {} - match ended", frame));
+                self.state = GameState::GameOver;
+                self.time_remaining = 0.0;
+            }
+        }
     }
 
     fn update(&mut self, dt: f32) {
         match self.state {
+            GameState::Reconnecting => {
+                if self.reconnect_timer > 0.0 {
+                    self.reconnect_timer -= dt;
+                }
+            }
+            GameState::Leaderboard => {
+                if self.network_mode == NetworkMode::Online {
+                    self.leaderboard_poll_timer -= dt;
+                    if self.leaderboard_poll_timer <= 0.0 {
+                        self.leaderboard_poll_timer = LEADERBOARD_POLL_INTERVAL;
+                        self.send_to_server(ClientMessage::FetchLeaderboard { since_version: self.leaderboard_version });
+                        self.send_to_server(ClientMessage::ListMatches);
+                    }
+                }
+            }
             GameState::Playing => {
                 self.time_remaining -= dt;
                 if self.time_remaining <= 0.0 {
@@ -393,6 +1235,26 @@ impl Game {
                     self.shake_timer -= dt;
                 }
 
+                // Tick and cull particle effects
+                for particle in &mut self.particles {
+                    particle.tick(dt);
+                }
+                self.particles.retain(|p| !p.is_dead());
+
+                // Decay emote bubbles
+                if let Some((_, timer)) = &mut self.own_emote {
+                    *timer -= dt;
+                    if *timer <= 0.0 {
+                        self.own_emote = None;
+                    }
+                }
+                if let Some((_, timer)) = &mut self.opponent_emote {
+                    *timer -= dt;
+                    if *timer <= 0.0 {
+                        self.opponent_emote = None;
+                    }
+                }
+
                 // Update garbage queue timer
                 if self.garbage_queue > 0 {
                     self.garbage_timer -= dt;
@@ -435,6 +1297,15 @@ impl Game {
                         self.apply_garbage();
                         self.pending_garbage = 0;
                     }
+
+                    // Advance the frame the replay log keys inputs against.
+                    self.match_frame += 1;
+
+                    if self.network_mode == NetworkMode::Online
+                        && self.match_frame % CHECKSUM_INTERVAL_FRAMES == 0
+                    {
+                        self.send_checksum();
+                    }
                 }
 
                 // Simulate opponent in offline mode
@@ -443,45 +1314,145 @@ impl Game {
                         self.opponent_score += ::rand::thread_rng().gen_range(10..50);
                     }
                 }
+
+                if self.network_mode == NetworkMode::Online {
+                    self.tick_lockstep();
+                }
+            }
+            GameState::Replay => {
+                // Mirror `Playing`'s cosmetic bookkeeping so a replay looks
+                // the same as the live match it was recorded from.
+                for booster in &mut self.boosters {
+                    if booster.cooldown_remaining > 0.0 {
+                        booster.cooldown_remaining -= dt;
+                        if booster.cooldown_remaining < 0.0 {
+                            booster.cooldown_remaining = 0.0;
+                        }
+                    }
+                }
+                if self.shake_timer > 0.0 {
+                    self.shake_timer -= dt;
+                }
+                for particle in &mut self.particles {
+                    particle.tick(dt);
+                }
+                self.particles.retain(|p| !p.is_dead());
+
+                if self.animation_timer > 0.0 {
+                    self.animation_timer -= dt;
+                } else {
+                    self.update_falling_gems(dt);
+                    if self.pending_garbage > 0 {
+                        self.apply_garbage();
+                        self.pending_garbage = 0;
+                    }
+                    self.tick_replay();
+                }
             }
             _ => {}
         }
 
+        // Auto-forfeit an online match nobody's watching. `touch_activity`
+        // only resets the clock on clicks, boosters, and key presses - not
+        // inbound server messages - so this fires on the local player's own
+        // inactivity, not the opponent's.
+        if self.network_mode == NetworkMode::Online
+            && matches!(self.state, GameState::Connecting | GameState::Playing | GameState::GameOver)
+            && get_time() - self.last_active_time > AFK_TIMEOUT
+        {
+            self.send_to_server(ClientMessage::Forfeit);
+            self.network_bridge = None;
+            self.disconnect_reason = Some("You were disconnected for being AFK".to_string());
+            self.state = GameState::Menu;
+            self.network_mode = NetworkMode::Offline;
+        }
+
         // Handle incoming network messages
         let mut messages = Vec::new();
+        let mut errors = Vec::new();
+        let mut dropped = false;
         if let Some(bridge) = &mut self.network_bridge {
             while let Some(msg) = bridge.try_recv() {
                 messages.push(msg);
             }
+            while let Some(err) = bridge.try_recv_error() {
+                errors.push(err);
+            }
+            dropped = bridge.is_disconnected();
         }
         for msg in messages {
             self.handle_server_message(msg);
         }
+        for err in errors {
+            self.handle_transport_error(err);
+        }
+
+        // A dropped socket mid-match shouldn't just end the game - retry
+        // with backoff instead of falling straight to GameOver. This is a
+        // fallback for disconnects the socket task couldn't report an
+        // explicit NetError for (e.g. a panic); handle_transport_error
+        // covers the cases it can name.
+        if dropped
+            && self.network_mode == NetworkMode::Online
+            && matches!(self.state, GameState::Playing | GameState::WaitingForMatch)
+        {
+            self.network_bridge = None;
+            self.disconnect_reason = Some("Connection lost - reconnecting...".to_string());
+            self.state = GameState::Reconnecting;
+            self.reconnect_timer = self.reconnect_backoff;
+        }
     }
 
     fn handle_server_message(&mut self, msg: ServerMessage) {
         match msg {
-            ServerMessage::AuthAccepted { player_id, username, elo, wins, losses, bricks, gold } => {
+            ServerMessage::HelloAck { accepted, server_version, min_supported } => {
+                if !accepted {
+                    println!(
+                        "Protocol rejected: client v{} vs server v{} (min v{})",
+                        PROTOCOL_VERSION, server_version, min_supported
+                    );
+                    self.disconnect_reason = Some(format!(
+                        "Update required: this client (v{}) is incompatible with the server (min v{})",
+                        PROTOCOL_VERSION, min_supported
+                    ));
+                    self.network_bridge = None;
+                    self.state = GameState::Menu;
+                    return;
+                }
+
+                let login_msg = match &self.session_token {
+                    Some(session_token) => ClientMessage::Resume {
+                        session_token: session_token.clone(),
+                        known_board_version: self.opponent_board_version,
+                    },
+                    None if self.register_mode => ClientMessage::Register {
+                        username: self.username.clone(),
+                        password: self.password.clone(),
+                    },
+                    None => ClientMessage::Login {
+                        username: self.username.clone(),
+                        password: self.password.clone(),
+                    },
+                };
+                self.send_to_server(login_msg);
+            }
+            ServerMessage::AuthAccepted { player_id, username, rating, rating_deviation, wins, losses, session_token } => {
                 println!("Authentication successful! Welcome {}", username);
                 self.username = username;
-                self.elo = elo;
+                self.rating = rating;
+                self.rating_deviation = rating_deviation;
                 self.wins = wins;
                 self.losses = losses;
-                self.bricks = bricks;
-                self.gold = gold;
+                self.session_token = Some(session_token);
 
                 // Check if we're connecting for leaderboard or for playing
                 if self.connecting_for_leaderboard {
-                    // Request leaderboard data
-                    if let Some(bridge) = &self.network_bridge {
-                        bridge.send(ClientMessage::FetchLeaderboard);
-                    }
+                    self.send_to_server(ClientMessage::FetchLeaderboard { since_version: self.leaderboard_version });
+                    self.send_to_server(ClientMessage::ListMatches);
                     self.connecting_for_leaderboard = false;
                 } else {
                     // Join queue automatically after authentication
-                    if let Some(bridge) = &self.network_bridge {
-                        bridge.send(ClientMessage::JoinQueue);
-                    }
+                    self.send_to_server(ClientMessage::JoinQueue);
                     self.state = GameState::WaitingForMatch;
                 }
             }
@@ -490,20 +1461,55 @@ impl Game {
                 self.disconnect_reason = Some(format!("Auth failed: {}", reason));
                 self.state = GameState::Login;
             }
-            ServerMessage::MatchResult { new_elo, elo_change, wins, losses, bricks, gold } => {
-                self.elo = new_elo;
+            ServerMessage::MatchResult { new_rating, rating_change, new_rd, wins, losses } => {
+                self.rating = new_rating;
+                self.rating_deviation = new_rd;
                 self.wins = wins;
                 self.losses = losses;
-                self.bricks = bricks;
-                self.gold = gold;
-                println!("Match result: ELO {} ({:+}), W/L: {}/{}, Bricks: {}, Gold: {}",
-                    new_elo, elo_change, wins, losses, bricks, gold);
-            }
-            ServerMessage::LeaderboardData { players } => {
-                self.leaderboard_data = players;
-                println!("Received leaderboard data with {} players", self.leaderboard_data.len());
+                println!("Match result: rating {:.0} ({:+.0}), RD {:.0}, W/L: {}/{}",
+                    new_rating, rating_change, new_rd, wins, losses);
+            }
+            ServerMessage::LeaderboardData { version, players } => {
+                // Re-sorting/re-allocating the table is wasted work if a
+                // slow-poll reply happens to arrive at the version we
+                // already have cached - only rebuild when it actually moved.
+                if version != self.leaderboard_version {
+                    self.leaderboard_data = players;
+                    self.leaderboard_version = version;
+                    println!("Received leaderboard data with {} players (v{})", self.leaderboard_data.len(), version);
+                }
                 // Transition to leaderboard state when data is received
                 self.state = GameState::Leaderboard;
+                self.leaderboard_poll_timer = LEADERBOARD_POLL_INTERVAL;
+            }
+            ServerMessage::LeaderboardUnchanged => {
+                // Our cached leaderboard_data is already current - just show it.
+                self.state = GameState::Leaderboard;
+                self.leaderboard_poll_timer = LEADERBOARD_POLL_INTERVAL;
+            }
+            ServerMessage::MatchList { matches } => {
+                self.match_list = matches;
+            }
+            ServerMessage::MatchHistory { matches, next_cursor } => {
+                self.match_history = matches;
+                self.match_history_cursor = next_cursor;
+            }
+            ServerMessage::HeadToHead { matches, wins, losses } => {
+                self.head_to_head = matches;
+                self.head_to_head_wins = wins;
+                self.head_to_head_losses = losses;
+            }
+            ServerMessage::OpponentInputFrame { frame, inputs } => {
+                match bincode::deserialize::<Vec<PlayerInput>>(&inputs) {
+                    Ok(inputs) => self.lockstep.receive_remote(frame, inputs),
+                    Err(e) => println!("Failed to decode lockstep input frame: {}", e),
+                }
+            }
+            ServerMessage::OpponentStateChecksum { frame, hash } => {
+                self.handle_opponent_checksum(frame, hash);
+            }
+            ServerMessage::Chat { from, text } => {
+                self.push_chat_line(format!("{}: {}", from, text));
             }
             ServerMessage::Connected { player_id } => {
                 println!("Connected with player ID: {}", player_id);
@@ -511,19 +1517,55 @@ impl Game {
             ServerMessage::Queued { position } => {
                 println!("In queue, position: {}", position);
             }
-            ServerMessage::MatchFound { game_id, opponent_id } => {
-                println!("Match found! Game ID: {}, Opponent: {}", game_id, opponent_id);
+            ServerMessage::MatchFound { game_id, opponent_id, opponent_name, win_probability } => {
+                println!(
+                    "Match found! Game ID: {}, Opponent: {} ({}), estimated win chance: {:.0}%",
+                    game_id, opponent_name, opponent_id, win_probability * 100.0
+                );
+                self.opponent_name = Some(opponent_name);
+                self.opponent_win_probability = Some(win_probability);
             }
-            ServerMessage::GameStarted { game_id } => {
+            ServerMessage::GameStarted { game_id, seed } => {
                 println!("Game started! ID: {}", game_id);
                 self.state = GameState::Playing;
+                self.current_game_id = Some(game_id);
                 self.score = 0;
                 self.opponent_score = 0;
+                self.opponent_grid = None;
+                self.opponent_energy = 0;
                 self.time_remaining = GAME_DURATION;
+                // Reseed from the server's shared seed and rebuild the board
+                // so both clients (and the server's replay checker) land on
+                // a byte-identical grid.
+                self.reseed_rng(seed);
+                self.lockstep = LockstepState::new();
+                self.initialize_board();
             }
             ServerMessage::OpponentSwap { row1, col1, row2, col2 } => {
                 println!("Opponent swapped ({},{}) with ({},{})", row1, col1, row2, col2);
-                // We don't visualize opponent's board, so just log it
+                // The grid itself comes from BoardSnapshot; this just logs
+                // the move for now.
+            }
+            ServerMessage::BoardSnapshot { grid, score, energy, version } => {
+                // Skip the decode (a fresh GRID_SIZE x GRID_SIZE allocation)
+                // when this is a version we've already applied.
+                if version != self.opponent_board_version {
+                    match decode_board(&grid) {
+                        Some(decoded) => {
+                            self.opponent_grid = Some(decoded);
+                            self.opponent_score = score;
+                            self.opponent_energy = energy;
+                            self.opponent_board_version = version;
+                        }
+                        None => {
+                            println!("Dropped malformed BoardSnapshot: expected {} bytes, got {}", GRID_SIZE * GRID_SIZE, grid.len());
+                        }
+                    }
+                }
+            }
+            ServerMessage::BoardUnchanged => {
+                // Our cached opponent grid from before the drop is still
+                // current; nothing to apply.
             }
             ServerMessage::ScoreUpdate { player_score, opponent_score } => {
                 self.score = player_score;
@@ -541,6 +1583,11 @@ impl Game {
             ServerMessage::OpponentActivatedBooster { booster_id } => {
                 println!("Opponent activated booster #{}", booster_id);
             }
+            ServerMessage::OpponentEmote { emote_id } => {
+                if let Some(emote) = Emote::from_id(emote_id) {
+                    self.opponent_emote = Some((emote, EMOTE_BUBBLE_DURATION));
+                }
+            }
             ServerMessage::GameOver { winner } => {
                 self.state = GameState::GameOver;
                 self.time_remaining = 0.0;
@@ -553,21 +1600,86 @@ impl Game {
             ServerMessage::OpponentRequestedRematch => {
                 self.handle_opponent_rematch_request();
             }
-            ServerMessage::RematchAccepted => {
-                self.handle_rematch_accepted();
+            ServerMessage::RematchAccepted { seed } => {
+                self.handle_rematch_accepted(seed);
             }
             ServerMessage::OpponentLeft => {
                 self.handle_opponent_left();
             }
-            ServerMessage::OpponentDisconnected => {
-                self.handle_opponent_disconnected();
+            ServerMessage::OpponentDisconnected { grace_seconds } => {
+                self.handle_opponent_disconnected(grace_seconds);
+            }
+            ServerMessage::OpponentReconnected => {
+                self.handle_opponent_reconnected();
+            }
+            ServerMessage::ResumeAccepted { game_id, seconds_remaining, player_score, opponent_score, pending_garbage, board_version: _ } => {
+                println!("Resumed game {}", game_id);
+                self.current_game_id = Some(game_id);
+                self.score = player_score;
+                self.opponent_score = opponent_score;
+                self.time_remaining = seconds_remaining as f32;
+                self.disconnect_reason = None;
+                self.reconnect_backoff = RECONNECT_INITIAL_BACKOFF;
+                self.state = GameState::Playing;
+                if pending_garbage > 0 {
+                    self.pending_garbage = pending_garbage;
+                }
+            }
+            ServerMessage::ResumeRejected => {
+                println!("Resume rejected - session no longer valid");
+                self.disconnect_reason = Some("Could not reconnect to your match".to_string());
+                self.state = GameState::GameOver;
             }
             ServerMessage::Error { message } => {
                 println!("Server error: {}", message);
+                // Surfaces things like a chat flood-protection notice (which
+                // only ever targets this client, never broadcast) where the
+                // player will actually see it, not just the console.
+                self.push_chat_line(format!("[system] {}", message));
             }
         }
     }
 
+    /// Drains every `ReplayFrame` due on the current `match_frame` from
+    /// `replay_playback` and feeds its inputs back through
+    /// `swap_gems`/`activate_special`/`activate_booster` - the same path a
+    /// live match uses - then advances past this frame. Ends the replay once
+    /// the log is exhausted.
+    fn tick_replay(&mut self) {
+        let due: Vec<PlayerInput> = match &mut self.replay_playback {
+            Some(playback) => {
+                let mut inputs = Vec::new();
+                while playback.next_frame_index < playback.log.frames.len()
+                    && playback.log.frames[playback.next_frame_index].frame == self.match_frame
+                {
+                    inputs.extend(playback.log.frames[playback.next_frame_index].inputs.iter().copied());
+                    playback.next_frame_index += 1;
+                }
+                inputs
+            }
+            None => Vec::new(),
+        };
+
+        for input in due {
+            match input {
+                PlayerInput::Swap { row1, col1, row2, col2 } => self.swap_gems(row1, col1, row2, col2),
+                PlayerInput::ActivateSpecial { row, col } => self.activate_special(row, col),
+                PlayerInput::ActivateBooster { booster_index } => self.activate_booster(booster_index),
+            }
+        }
+
+        self.match_frame += 1;
+
+        let exhausted = self
+            .replay_playback
+            .as_ref()
+            .map(|p| p.next_frame_index >= p.log.frames.len())
+            .unwrap_or(true);
+        if exhausted {
+            self.state = GameState::GameOver;
+        }
+    }
+
     fn update_falling_gems(&mut self, dt: f32) {
         let mut any_falling = false;
 
@@ -592,7 +1704,42 @@ impl Game {
         }
     }
 
+    /// Refreshes `last_active_time`, pushing the AFK forfeit deadline back.
+    /// Called only from click/booster/key handling - genuine local-player
+    /// action - never from the network receive path, since the opponent's
+    /// own traffic (e.g. a `TimeUpdate` every second) would otherwise reset
+    /// the clock for a player who's actually walked away.
+    fn touch_activity(&mut self) {
+        self.last_active_time = get_time();
+    }
+
+    /// Submits the Login screen: applies the typed username and, if the
+    /// optional server-address field was filled in, points `network_config`
+    /// at it instead of the built-in default before dialing. A blank field
+    /// leaves `network_config.url` untouched so casual players who never
+    /// touch the second box still connect to the default server.
+    fn submit_login(&mut self) {
+        self.username = self.pending_username.text.clone();
+        self.password = self.pending_password.text.clone();
+
+        let target = self.connect_target_input.text.trim().to_string();
+        if target.is_empty() {
+            self.connect_target = None;
+        } else {
+            self.network_config.url = if target.contains("://") {
+                target.clone()
+            } else {
+                format!("wss://{}", target)
+            };
+            self.connect_target = Some(target);
+        }
+
+        self.state = GameState::Connecting;
+        self.network_mode = NetworkMode::Online;
+    }
+
     fn handle_click(&mut self, x: f32, y: f32) {
+        self.touch_activity();
         if self.state != GameState::Playing || self.animation_timer > 0.0 {
             return;
         }
@@ -644,10 +1791,10 @@ impl Game {
     fn swap_gems(&mut self, row1: usize, col1: usize, row2: usize, col2: usize) {
         // Send swap message to server (online mode)
         if self.network_mode == NetworkMode::Online {
-            if let Some(bridge) = &self.network_bridge {
-                bridge.send(ClientMessage::SwapGems { row1, col1, row2, col2 });
-            }
+            self.send_to_server(ClientMessage::SwapGems { row1, col1, row2, col2 });
+            self.lockstep.queue_input(PlayerInput::Swap { row1, col1, row2, col2 });
         }
+        self.record_input(PlayerInput::Swap { row1, col1, row2, col2 });
 
         // Check for special gem combos BEFORE swapping
         let gem1 = self.grid[row1][col1];
@@ -755,20 +1902,132 @@ impl Game {
         v_count >= 3
     }
 
+    /// Flings a handful of sparks outward from `(row, col)` in `gem_type`'s
+    /// color; called once per gem in the removal loop of `resolve_match_wave`.
+    fn spawn_shatter(&mut self, row: usize, col: usize, gem_type: GemType) {
+        let cx = col as f32 * GEM_SIZE + GEM_SIZE / 2.0;
+        let cy = row as f32 * GEM_SIZE + GEM_SIZE / 2.0;
+        let color = gem_type.color();
+        for _ in 0..5 {
+            self.particles.push(Particle::spark(cx, cy, color, &mut self.rng));
+        }
+    }
+
+    /// Sweeps a beam along the row and column a drill just cleared.
+    fn spawn_drill_beams(&mut self, row: usize, col: usize) {
+        self.particles.push(Particle::drill_beam_row(row));
+        self.particles.push(Particle::drill_beam_col(col));
+    }
+
+    /// Expands a detonation ring centered on `(row, col)`.
+    fn spawn_explosion(&mut self, row: usize, col: usize, color: Color) {
+        let cx = col as f32 * GEM_SIZE + GEM_SIZE / 2.0;
+        let cy = row as f32 * GEM_SIZE + GEM_SIZE / 2.0;
+        self.particles.push(Particle::explosion(cx, cy, color));
+    }
+
+    /// Floats `text` upward from `(row, col)` - score gains and
+    /// "-N Incoming Blocked!" cancellations alike.
+    fn spawn_floating_text(&mut self, row: usize, col: usize, text: impl Into<String>, color: Color) {
+        let cx = col as f32 * GEM_SIZE + GEM_SIZE / 2.0 - 10.0;
+        let cy = row as f32 * GEM_SIZE;
+        self.particles.push(Particle::floating_text(cx, cy, text.into(), color));
+    }
+
+    /// Resolves matches to a standstill, including cascades: gems that fall
+    /// into a new match after gravity settles are re-scored instead of
+    /// lost. `chain` starts at 1 for the wave triggered by the player's
+    /// swap and increments for every subsequent wave gravity produces,
+    /// multiplying both the score and the garbage pressure a wave sends so
+    /// a 4-chain hits far harder than four isolated matches would.
+    ///
+    /// Every wave still resolves instantly within this one call rather than
+    /// being spread across animation frames (computing the next settled
+    /// state into a back buffer while the current one is still being drawn,
+    /// then swapping) — that's follow-up work, not required for the reward
+    /// math itself to be correct.
     fn check_and_remove_matches(&mut self) {
-        let matches = self.find_all_matches();
+        let mut chain: u32 = 0;
+        let mut total_garbage_to_send: u32 = 0;
+        let mut total_garbage_cancelled: u32 = 0;
 
-        if matches.is_empty() {
+        loop {
+            let matches = self.find_all_matches();
+            if matches.is_empty() {
+                break;
+            }
+            chain += 1;
+
+            let (garbage_to_send, garbage_cancelled) = self.resolve_match_wave(&matches, chain);
+            total_garbage_to_send += garbage_to_send;
+            total_garbage_cancelled += garbage_cancelled;
+
+            self.apply_gravity();
+        }
+
+        if chain == 0 {
             return;
         }
 
-        // Calculate energy and garbage from matches
-        let total_gems = matches.len();
-        let mut garbage_to_send = 0;
-        let mut garbage_cancelled = 0;
+        if chain > 1 {
+            println!("{}-chain!", chain);
+            self.shake_timer = self.shake_timer.max((0.15 * chain as f32).min(1.0));
+        }
+
+        // Apply garbage cancellation
+        if total_garbage_cancelled > 0 && self.garbage_queue > 0 {
+            let actually_cancelled = total_garbage_cancelled.min(self.garbage_queue as u32);
+            self.garbage_queue = self.garbage_queue.saturating_sub(actually_cancelled as u8);
+
+            // Visual feedback for cancellation
+            if actually_cancelled > 0 {
+                println!("-{} Incoming Blocked!", actually_cancelled);
+                self.spawn_floating_text(0, GRID_SIZE / 2, format!("-{} Incoming Blocked!", actually_cancelled), GREEN);
+                self.shake_timer = 0.2; // Small shake for feedback
+            }
+
+            // Reset garbage timer if queue is now empty
+            if self.garbage_queue == 0 {
+                self.garbage_timer = 0.0;
+            }
+        }
+
+        // Send garbage to opponent
+        if total_garbage_to_send > 0 && self.network_mode == NetworkMode::Online {
+            self.send_to_server(ClientMessage::SendGarbage {
+                amount: total_garbage_to_send.min(u8::MAX as u32) as u8,
+            });
+        }
+
+        // Send score update to server, plus our settled board so the
+        // opponent's opponent_grid and any spectators stay in sync.
+        if self.network_mode == NetworkMode::Online {
+            self.send_to_server(ClientMessage::ScoreUpdate { score: self.score });
+            self.send_to_server(ClientMessage::BoardUpdate {
+                grid: encode_board(&self.grid),
+                score: self.score,
+                energy: self.energy,
+            });
+        }
+    }
+
+    /// Marks, specializes, and removes a single wave of `matches`, awarding
+    /// `chain`-scaled score/energy. Returns `(garbage_to_send,
+    /// garbage_cancelled)` for this wave, already scaled by `chain`, for the
+    /// caller to accumulate across however many waves a cascade produces.
+    fn resolve_match_wave(&mut self, matches: &[MatchType], chain: u32) -> (u32, u32) {
+        let total_gems: usize = matches
+            .iter()
+            .map(|m| match m {
+                MatchType::Line(positions) => positions.len(),
+                MatchType::LShape(positions) | MatchType::TShape(positions) => positions.len(),
+            })
+            .sum();
+        let mut garbage_to_send: u32 = 0;
+        let mut garbage_cancelled: u32 = 0;
 
         // Mark gems for removal and create specials
-        for match_info in &matches {
+        for match_info in matches {
             match match_info {
                 MatchType::Line(positions) => {
                     for &(r, c) in positions {
@@ -812,52 +2071,21 @@ impl Game {
             for col in 0..GRID_SIZE {
                 if let Some(gem) = self.grid[row][col] {
                     if gem.marked_for_removal {
+                        self.spawn_shatter(row, col, gem.gem_type);
                         self.grid[row][col] = None;
                     }
                 }
             }
         }
 
-        // Update score and energy
-        self.score += total_gems as u32 * 10;
+        // Update score and energy; rewards escalate with chain depth.
+        self.score += total_gems as u32 * 10 * chain;
         self.energy = (self.energy + total_gems as u32).min(100);
-
-        // Apply garbage cancellation
-        if garbage_cancelled > 0 && self.garbage_queue > 0 {
-            let actually_cancelled = garbage_cancelled.min(self.garbage_queue as u32);
-            self.garbage_queue = self.garbage_queue.saturating_sub(actually_cancelled as u8);
-
-            // Visual feedback for cancellation
-            if actually_cancelled > 0 {
-                println!("-{} Incoming Blocked!", actually_cancelled);
-                self.shake_timer = 0.2; // Small shake for feedback
-            }
-
-            // Reset garbage timer if queue is now empty
-            if self.garbage_queue == 0 {
-                self.garbage_timer = 0.0;
-            }
-        }
-
-        // Send garbage to opponent
-        if garbage_to_send > 0 && self.network_mode == NetworkMode::Online {
-            if let Some(bridge) = &self.network_bridge {
-                bridge.send(ClientMessage::SendGarbage { amount: garbage_to_send });
-            }
-        }
-
         if total_gems >= 4 {
-            self.score += 20;
+            self.score += 20 * chain;
         }
 
-        // Send score update to server
-        if self.network_mode == NetworkMode::Online {
-            if let Some(bridge) = &self.network_bridge {
-                bridge.send(ClientMessage::ScoreUpdate { score: self.score });
-            }
-        }
-
-        self.apply_gravity();
+        (garbage_to_send * chain, garbage_cancelled * chain)
     }
 
     fn find_all_matches(&self) -> Vec<MatchType> {
@@ -999,10 +2227,10 @@ impl Game {
     fn activate_special(&mut self, row: usize, col: usize) {
         // Send activation message to server
         if self.network_mode == NetworkMode::Online {
-            if let Some(bridge) = &self.network_bridge {
-                bridge.send(ClientMessage::ActivateSpecial { row, col });
-            }
+            self.send_to_server(ClientMessage::ActivateSpecial { row, col });
+            self.lockstep.queue_input(PlayerInput::ActivateSpecial { row, col });
         }
+        self.record_input(PlayerInput::ActivateSpecial { row, col });
 
         if let Some(gem) = self.grid[row][col] {
             match gem.gem_type {
@@ -1029,6 +2257,8 @@ impl Game {
             self.grid[r][col] = None;
         }
 
+        self.spawn_drill_beams(row, col);
+        self.spawn_floating_text(row, col, "+50", YELLOW);
         self.score += 50;
 
         // Cancel garbage from queue
@@ -1036,6 +2266,7 @@ impl Game {
             let cancelled = 1u8.min(self.garbage_queue);
             self.garbage_queue -= cancelled;
             println!("-{} Incoming Blocked!", cancelled);
+            self.spawn_floating_text(row, col, format!("-{} Incoming Blocked!", cancelled), GREEN);
             if self.garbage_queue == 0 {
                 self.garbage_timer = 0.0;
             }
@@ -1058,6 +2289,8 @@ impl Game {
             }
         }
 
+        self.spawn_explosion(row, col, GemType::Barrel.color());
+        self.spawn_floating_text(row, col, "+40", YELLOW);
         self.score += 40;
 
         // Cancel garbage from queue
@@ -1065,6 +2298,7 @@ impl Game {
             let cancelled = 2u8.min(self.garbage_queue);
             self.garbage_queue -= cancelled;
             println!("-{} Incoming Blocked!", cancelled);
+            self.spawn_floating_text(row, col, format!("-{} Incoming Blocked!", cancelled), GREEN);
             if self.garbage_queue == 0 {
                 self.garbage_timer = 0.0;
             }
@@ -1076,7 +2310,7 @@ impl Game {
 
     fn activate_mixer(&mut self, row: usize, col: usize) {
         // Remove all gems of a random color
-        let target_color = GemType::random_basic();
+        let target_color = GemType::random_basic(&mut self.rng);
 
         for r in 0..GRID_SIZE {
             for c in 0..GRID_SIZE {
@@ -1089,6 +2323,8 @@ impl Game {
         }
 
         self.grid[row][col] = None;
+        self.spawn_explosion(row, col, target_color.color());
+        self.spawn_floating_text(row, col, "+100", YELLOW);
         self.score += 100;
 
         // Cancel garbage from queue
@@ -1096,6 +2332,7 @@ impl Game {
             let cancelled = 3u8.min(self.garbage_queue);
             self.garbage_queue -= cancelled;
             println!("-{} Incoming Blocked!", cancelled);
+            self.spawn_floating_text(row, col, format!("-{} Incoming Blocked!", cancelled), GREEN);
             if self.garbage_queue == 0 {
                 self.garbage_timer = 0.0;
             }
@@ -1126,6 +2363,8 @@ impl Game {
                     self.grid[r][center_col] = None;
                 }
 
+                self.spawn_drill_beams(center_row, center_col);
+                self.spawn_floating_text(center_row, center_col, "+150", YELLOW);
                 self.score += 150;
                 self.shake_timer = 0.4; // Medium shake
             }
@@ -1151,6 +2390,9 @@ impl Game {
                     }
                 }
 
+                self.particles.push(Particle::drill_beam_row(center_row));
+                self.spawn_explosion(center_row, center_col, GemType::Barrel.color());
+                self.spawn_floating_text(center_row, center_col, "+120", YELLOW);
                 self.score += 120;
                 self.shake_timer = 0.35; // Medium shake
             }
@@ -1170,12 +2412,14 @@ impl Game {
                     }
                 }
 
+                self.spawn_explosion(center_row, center_col, GemType::Barrel.color());
+                self.spawn_floating_text(center_row, center_col, "+200", YELLOW);
                 self.score += 200;
                 self.shake_timer = 0.5; // Strong shake
             }
             (GemType::Mixer, GemType::Drill) | (GemType::Drill, GemType::Mixer) => {
                 // Convert all gems of one color to Drills
-                let target_color = GemType::random_basic();
+                let target_color = GemType::random_basic(&mut self.rng);
 
                 for r in 0..GRID_SIZE {
                     for c in 0..GRID_SIZE {
@@ -1187,12 +2431,16 @@ impl Game {
                     }
                 }
 
+                let center_row = (row1 + row2) / 2;
+                let center_col = (col1 + col2) / 2;
+                self.spawn_explosion(center_row, center_col, GemType::Drill.color());
+                self.spawn_floating_text(center_row, center_col, "+250", YELLOW);
                 self.score += 250;
                 self.shake_timer = 0.6; // Strong shake
             }
             (GemType::Mixer, GemType::Barrel) | (GemType::Barrel, GemType::Mixer) => {
                 // Convert all gems of one color to Barrels
-                let target_color = GemType::random_basic();
+                let target_color = GemType::random_basic(&mut self.rng);
 
                 for r in 0..GRID_SIZE {
                     for c in 0..GRID_SIZE {
@@ -1204,6 +2452,10 @@ impl Game {
                     }
                 }
 
+                let center_row = (row1 + row2) / 2;
+                let center_col = (col1 + col2) / 2;
+                self.spawn_explosion(center_row, center_col, GemType::Barrel.color());
+                self.spawn_floating_text(center_row, center_col, "+300", YELLOW);
                 self.score += 300;
                 self.shake_timer = 0.7; // Very strong shake
             }
@@ -1215,6 +2467,12 @@ impl Game {
                     }
                 }
 
+                for r in (0..GRID_SIZE).step_by(2) {
+                    for c in (0..GRID_SIZE).step_by(2) {
+                        self.spawn_explosion(r, c, WHITE);
+                    }
+                }
+                self.spawn_floating_text(GRID_SIZE / 2, GRID_SIZE / 2 - 1, "+500", YELLOW);
                 self.score += 500;
                 self.shake_timer = 1.0; // MEGA shake!
             }
@@ -1237,10 +2495,16 @@ impl Game {
         self.shake_timer = 0.3;
     }
 
-    fn handle_opponent_disconnected(&mut self) {
-        self.disconnect_reason = Some("Opponent Disconnected - You Win!".to_string());
-        self.state = GameState::GameOver;
-        self.time_remaining = 0.0;
+    // The server pauses the countdown while the opponent is gone (see
+    // `opponent_reconnect_grace`/`draw_game`'s banner), so this doesn't end
+    // the match - only `GameOver` does, whether from a normal finish or the
+    // server finalizing a forfeit once the grace period runs out.
+    fn handle_opponent_disconnected(&mut self, grace_seconds: u64) {
+        self.opponent_reconnect_grace = Some(grace_seconds);
+    }
+
+    fn handle_opponent_reconnected(&mut self) {
+        self.opponent_reconnect_grace = None;
     }
 
     fn handle_opponent_left(&mut self) {
@@ -1253,12 +2517,17 @@ impl Game {
         self.opponent_requested_rematch = true;
     }
 
-    fn handle_rematch_accepted(&mut self) {
-        // Both players agreed to rematch - reset and start new game
+    fn handle_rematch_accepted(&mut self, seed: u64) {
+        // Both players agreed to rematch - reseed before reset_game() draws
+        // the new board, so both clients start the new match in lockstep
+        // instead of wherever the prior match happened to leave the stream.
+        self.reseed_rng(seed);
+        self.lockstep = LockstepState::new();
         self.reset_game();
     }
 
     fn activate_booster(&mut self, booster_index: usize) {
+        self.touch_activity();
         if booster_index >= self.boosters.len() {
             return;
         }
@@ -1281,16 +2550,15 @@ impl Game {
                 for col in 0..GRID_SIZE {
                     if let Some(gem) = self.grid[GRID_SIZE - 1][col] {
                         if gem.gem_type.is_garbage() {
-                            self.grid[GRID_SIZE - 1][col] = Some(Gem::new(GemType::random_basic()));
+                            self.grid[GRID_SIZE - 1][col] = Some(Gem::new(GemType::random_basic(&mut self.rng)));
                         }
                     }
                 }
             }
             BoosterType::BarrelBurst => {
                 // Spawn a random Barrel on the board
-                let mut rng = ::rand::thread_rng();
-                let row = rng.gen_range(0..GRID_SIZE);
-                let col = rng.gen_range(0..GRID_SIZE);
+                let row = self.rng.gen_range(0..GRID_SIZE);
+                let col = self.rng.gen_range(0..GRID_SIZE);
                 self.grid[row][col] = Some(Gem::new(GemType::Barrel));
             }
         }
@@ -1298,11 +2566,45 @@ impl Game {
         // Set cooldown
         self.boosters[booster_index].cooldown_remaining = 5.0;
 
-        // Send network message if online
+        // Send network message if online
+        if self.network_mode == NetworkMode::Online {
+            self.send_to_server(ClientMessage::ActivateBooster { booster_id: booster.booster_type.id() });
+            self.lockstep.queue_input(PlayerInput::ActivateBooster { booster_index });
+        }
+        self.record_input(PlayerInput::ActivateBooster { booster_index });
+    }
+
+    fn send_emote(&mut self, emote: Emote) {
+        self.own_emote = Some((emote, EMOTE_BUBBLE_DURATION));
+
+        if self.network_mode == NetworkMode::Online {
+            self.send_to_server(ClientMessage::SendEmote { emote_id: emote.id() });
+        }
+    }
+
+    /// Sends `chat_input`'s current text, if non-empty. Offline matches have
+    /// no opponent to relay to or flood-protect against, so it's just
+    /// echoed straight into `chat_log`; online ones go through the server's
+    /// flood guard and only appear once it echoes `ServerMessage::Chat` back.
+    fn send_chat(&mut self) {
+        let text = std::mem::take(&mut self.chat_input);
+        if text.trim().is_empty() {
+            return;
+        }
+
         if self.network_mode == NetworkMode::Online {
-            if let Some(bridge) = &self.network_bridge {
-                bridge.send(ClientMessage::ActivateBooster { booster_id: booster.booster_type.id() });
-            }
+            self.send_to_server(ClientMessage::Chat { text });
+        } else {
+            self.push_chat_line(format!("You: {}", text));
+        }
+    }
+
+    /// Appends `line` to `chat_log`, dropping the oldest once past
+    /// `CHAT_LOG_MAX_LINES`.
+    fn push_chat_line(&mut self, line: String) {
+        self.chat_log.push(line);
+        if self.chat_log.len() > CHAT_LOG_MAX_LINES {
+            self.chat_log.remove(0);
         }
     }
 
@@ -1325,6 +2627,11 @@ impl Game {
         }
     }
 
+    /// Refills each column after matches are cleared. Invariant: new gems
+    /// are drawn from `self.rng` column-by-column (0..GRID_SIZE), and within
+    /// each column top-to-bottom (the `for row in 0..write_row` loop below).
+    /// This order must stay fixed, since it's what keeps two online clients'
+    /// RNG streams in lockstep from the same seed.
     fn apply_gravity(&mut self) {
         for col in 0..GRID_SIZE {
             let mut write_row = GRID_SIZE;
@@ -1340,7 +2647,7 @@ impl Game {
             }
 
             for row in 0..write_row {
-                let mut new_gem = Gem::new(GemType::random_basic());
+                let mut new_gem = Gem::new(GemType::random_basic(&mut self.rng));
                 new_gem.y_offset = (write_row - row) as f32 * GEM_SIZE;
                 new_gem.is_falling = true;
                 self.grid[row][col] = Some(new_gem);
@@ -1355,12 +2662,16 @@ impl Game {
 
         match self.state {
             GameState::Menu => self.draw_menu(),
+            GameState::ServerBrowser => self.draw_server_browser(),
             GameState::Login => self.draw_login(),
             GameState::Connecting => self.draw_connecting(),
             GameState::WaitingForMatch => self.draw_waiting(),
             GameState::Playing => self.draw_game(),
             GameState::GameOver => self.draw_game_over(),
             GameState::Leaderboard => self.draw_leaderboard(),
+            GameState::Reconnecting => self.draw_reconnecting(),
+            GameState::Spectating => self.draw_spectating(),
+            GameState::Replay => self.draw_game(),
         }
     }
 
@@ -1402,11 +2713,17 @@ impl Game {
         draw_rectangle(leaderboard_x, leaderboard_y, 200.0, 50.0, Color::from_rgba(200, 100, 255, 255));
         draw_text("LEADERBOARD", leaderboard_x + 25.0, leaderboard_y + 33.0, 25.0, WHITE);
 
+        // Load replay button
+        let load_replay_x = screen_width / 2.0 - 100.0;
+        let load_replay_y = screen_height / 2.0 + 190.0;
+        draw_rectangle(load_replay_x, load_replay_y, 200.0, 50.0, Color::from_rgba(100, 100, 100, 255));
+        draw_text("LOAD REPLAY", load_replay_x + 28.0, load_replay_y + 33.0, 25.0, WHITE);
+
         // Instructions
         draw_text(
             "Match 3+ gems | Double-tap specials",
             screen_width / 2.0 - 150.0,
-            screen_height / 2.0 + 190.0,
+            screen_height / 2.0 + 260.0,
             18.0,
             LIGHTGRAY,
         );
@@ -1417,7 +2734,7 @@ impl Game {
         let screen_height = screen_height();
 
         draw_text(
-            "ENTER USERNAME",
+            if self.register_mode { "CREATE ACCOUNT" } else { "LOGIN" },
             screen_width / 2.0 - 130.0,
             screen_height / 2.0 - 100.0,
             40.0,
@@ -1442,38 +2759,104 @@ impl Game {
         let input_height = 50.0;
 
         draw_rectangle(input_x, input_y, input_width, input_height, DARKGRAY);
-        draw_rectangle_lines(input_x, input_y, input_width, input_height, 2.0, WHITE);
+        let input_border = if self.login_focus == LoginField::Username { YELLOW } else { WHITE };
+        draw_rectangle_lines(input_x, input_y, input_width, input_height, 2.0, input_border);
 
         // Display username being typed
         let display_text = if self.pending_username.is_empty() {
             "Type your username..."
         } else {
-            &self.pending_username
+            &self.pending_username.text
         };
         let text_color = if self.pending_username.is_empty() { GRAY } else { WHITE };
         draw_text(display_text, input_x + 10.0, input_y + 33.0, 25.0, text_color);
 
-        // Continue button (only enabled if username is not empty)
+        // Blinking caret at the cursor's text position, in whichever field
+        // currently has focus.
+        if get_time() % 1.0 < 0.5 && self.login_focus == LoginField::Username {
+            let cursor_text = &self.pending_username.text[..self.pending_username.cursor];
+            let cursor_x = input_x + 10.0 + measure_text(cursor_text, None, 25, 1.0).width;
+            draw_rectangle(cursor_x, input_y + 10.0, 2.0, 30.0, WHITE);
+        }
+
+        // Password input box - displayed masked with one asterisk per
+        // character so the cursor position still lines up with the typed text.
+        let password_x = input_x;
+        let password_y = screen_height / 2.0 + 30.0;
+        let password_width = input_width;
+        let password_height = 45.0;
+
+        draw_rectangle(password_x, password_y, password_width, password_height, DARKGRAY);
+        let password_border = if self.login_focus == LoginField::Password { YELLOW } else { WHITE };
+        draw_rectangle_lines(password_x, password_y, password_width, password_height, 2.0, password_border);
+
+        let masked: String = "*".repeat(self.pending_password.text.chars().count());
+        let password_display = if self.pending_password.is_empty() { "Password" } else { masked.as_str() };
+        let password_color = if self.pending_password.is_empty() { GRAY } else { WHITE };
+        draw_text(password_display, password_x + 10.0, password_y + 30.0, 20.0, password_color);
+
+        if get_time() % 1.0 < 0.5 && self.login_focus == LoginField::Password {
+            let masked_cursor = "*".repeat(self.pending_password.text[..self.pending_password.cursor].chars().count());
+            let cursor_x = password_x + 10.0 + measure_text(&masked_cursor, None, 20, 1.0).width;
+            draw_rectangle(cursor_x, password_y + 7.0, 2.0, 28.0, WHITE);
+        }
+
+        // Server address / room code input box (optional - blank keeps the
+        // built-in default server). Reuses UsernameEditor's char-input
+        // handling for a third field.
+        let target_x = input_x;
+        let target_y = screen_height / 2.0 + 85.0;
+        let target_width = input_width;
+        let target_height = 45.0;
+
+        draw_rectangle(target_x, target_y, target_width, target_height, DARKGRAY);
+        let target_border = if self.login_focus == LoginField::ServerTarget { YELLOW } else { WHITE };
+        draw_rectangle_lines(target_x, target_y, target_width, target_height, 2.0, target_border);
+
+        let target_display = if self.connect_target_input.is_empty() {
+            "Server host:port or room code (optional)"
+        } else {
+            &self.connect_target_input.text
+        };
+        let target_color = if self.connect_target_input.is_empty() { GRAY } else { WHITE };
+        draw_text(target_display, target_x + 10.0, target_y + 30.0, 20.0, target_color);
+
+        if get_time() % 1.0 < 0.5 && self.login_focus == LoginField::ServerTarget {
+            let cursor_text = &self.connect_target_input.text[..self.connect_target_input.cursor];
+            let cursor_x = target_x + 10.0 + measure_text(cursor_text, None, 20, 1.0).width;
+            draw_rectangle(cursor_x, target_y + 7.0, 2.0, 28.0, WHITE);
+        }
+
+        // Login/Register mode toggle
+        let toggle_text = if self.register_mode {
+            "Already have an account? Log in"
+        } else {
+            "New player? Register"
+        };
+        draw_text(toggle_text, screen_width / 2.0 - 150.0, screen_height / 2.0 + 155.0, 18.0, SKYBLUE);
+
+        // Continue button (only enabled if username and password are filled in)
         let button_x = screen_width / 2.0 - 100.0;
-        let button_y = screen_height / 2.0 + 50.0;
-        let button_enabled = !self.pending_username.is_empty();
+        let button_y = screen_height / 2.0 + 170.0;
+        let button_enabled = !self.pending_username.is_empty() && !self.pending_password.is_empty();
         let button_color = if button_enabled { GREEN } else { GRAY };
 
         draw_rectangle(button_x, button_y, 200.0, 50.0, button_color);
-        draw_text("CONTINUE", button_x + 40.0, button_y + 33.0, 25.0, WHITE);
+        let button_label = if self.register_mode { "REGISTER" } else { "CONTINUE" };
+        draw_text(button_label, button_x + 40.0, button_y + 33.0, 25.0, WHITE);
 
         // Instructions
         draw_text(
             "Press ENTER or click CONTINUE",
             screen_width / 2.0 - 140.0,
-            screen_height / 2.0 + 130.0,
+            screen_height / 2.0 + 250.0,
             18.0,
             LIGHTGRAY,
         );
 
         // Back button
         let back_x = screen_width / 2.0 - 100.0;
-        let back_y = screen_height / 2.0 + 170.0;
+        let back_y = screen_height / 2.0 + 290.0;
         draw_rectangle(back_x, back_y, 200.0, 40.0, Color::from_rgba(100, 100, 100, 255));
         draw_text("BACK", back_x + 75.0, back_y + 27.0, 20.0, WHITE);
     }
@@ -1491,6 +2874,27 @@ impl Game {
         );
     }
 
+    fn draw_reconnecting(&self) {
+        let screen_width = screen_width();
+        let screen_height = screen_height();
+
+        draw_text(
+            "Connection lost - reconnecting...",
+            screen_width / 2.0 - 220.0,
+            screen_height / 2.0 - 20.0,
+            30.0,
+            RED,
+        );
+
+        draw_text(
+            &format!("Next attempt in {:.1}s", self.reconnect_timer.max(0.0)),
+            screen_width / 2.0 - 120.0,
+            screen_height / 2.0 + 20.0,
+            22.0,
+            LIGHTGRAY,
+        );
+    }
+
     fn draw_waiting(&self) {
         let screen_width = screen_width();
         let screen_height = screen_height();
@@ -1522,11 +2926,36 @@ impl Game {
         );
 
         draw_text(
-            &format!("Opponent: {}", self.opponent_score),
+            &format!("{}: {}", self.opponent_name.as_deref().unwrap_or("Opponent"), self.opponent_score),
             screen_width() - 230.0, 35.0, 28.0,
             Color::from_rgba(255, 100, 100, 255),
         );
 
+        // The match timer is paused server-side for as long as this shows -
+        // see `opponent_reconnect_grace`.
+        if let Some(grace_seconds) = self.opponent_reconnect_grace {
+            draw_text(
+                &format!("Opponent disconnected - waiting up to {}s to reconnect...", grace_seconds),
+                20.0, 115.0, 22.0, ORANGE,
+            );
+        }
+
+        // Emote bubbles, near the board on the side each player occupies
+        if let Some((emote, _)) = self.own_emote {
+            let bubble_x = BOARD_OFFSET_X;
+            let bubble_y = BOARD_OFFSET_Y - 40.0;
+            draw_rectangle(bubble_x, bubble_y, 110.0, 32.0, emote.color());
+            draw_rectangle_lines(bubble_x, bubble_y, 110.0, 32.0, 2.0, WHITE);
+            draw_text(emote.label(), bubble_x + 8.0, bubble_y + 22.0, 22.0, BLACK);
+        }
+        if let Some((emote, _)) = self.opponent_emote {
+            let bubble_x = screen_width() - 230.0;
+            let bubble_y = BOARD_OFFSET_Y - 40.0;
+            draw_rectangle(bubble_x, bubble_y, 110.0, 32.0, emote.color());
+            draw_rectangle_lines(bubble_x, bubble_y, 110.0, 32.0, 2.0, WHITE);
+            draw_text(emote.label(), bubble_x + 8.0, bubble_y + 22.0, 22.0, BLACK);
+        }
+
         // Energy bar
         let energy_x = 20.0;
         let energy_y = 95.0;
@@ -1539,6 +2968,16 @@ impl Game {
         draw_rectangle_lines(energy_x, energy_y, energy_width, energy_height, 2.0, WHITE);
         draw_text(&format!("Energy: {}/100", self.energy), energy_x, energy_y - 5.0, 18.0, WHITE);
 
+        // Emote bar - click a box or press its F-key (see `emote_bar_rect`
+        // and the click handler in `main`) to send that emote.
+        for (i, emote) in Emote::ALL.iter().enumerate() {
+            let (ex, ey, ew, eh) = emote_bar_rect(i);
+            draw_rectangle(ex, ey, ew, eh, emote.color());
+            draw_rectangle_lines(ex, ey, ew, eh, 1.0, WHITE);
+            draw_text(emote.label(), ex + 4.0, ey + 18.0, 14.0, BLACK);
+            draw_text(&format!("F{}", i + 1), ex + 2.0, ey - 3.0, 12.0, LIGHTGRAY);
+        }
+
         // Resource HUD (Bricks and Gold) - displayed next to energy
         let resource_x = energy_x + energy_width + 30.0;
         let resource_y = 95.0;
@@ -1659,20 +3098,73 @@ impl Game {
             0.0
         };
 
-        // Grid
+        self.draw_board(&self.grid, BOARD_OFFSET_X + shake_x, BOARD_OFFSET_Y + shake_y, self.selected);
+        self.draw_particles(BOARD_OFFSET_X + shake_x, BOARD_OFFSET_Y + shake_y);
+
+        // Opponent's board, reported via BoardSnapshot, mirrored onto the
+        // right side of the screen so both boards are visible during a live
+        // match (not just while spectating).
+        if let Some(opponent_grid) = &self.opponent_grid {
+            let opp_x = screen_width() - BOARD_OFFSET_X - GRID_SIZE as f32 * GEM_SIZE;
+            draw_text("OPPONENT BOARD", opp_x, BOARD_OFFSET_Y - 15.0, 18.0, LIGHTGRAY);
+            self.draw_board(opponent_grid, opp_x, BOARD_OFFSET_Y, None);
+        }
+
+        self.draw_chat();
+    }
+
+    /// Draws the in-match chat panel: a scrollback of the last
+    /// `CHAT_LOG_MAX_LINES` lines below the board, plus an input row showing
+    /// either the line being typed (while `chat_active`) or a hint to press
+    /// Enter to start one.
+    fn draw_chat(&self) {
+        let panel_x = BOARD_OFFSET_X;
+        let panel_y = BOARD_OFFSET_Y + GRID_SIZE as f32 * GEM_SIZE + 10.0;
+        let panel_width = GRID_SIZE as f32 * GEM_SIZE;
+        let line_height = 18.0;
+        let log_height = CHAT_LOG_MAX_LINES as f32 * line_height;
+
+        draw_rectangle(panel_x, panel_y, panel_width, log_height, Color::from_rgba(10, 10, 20, 200));
+        draw_rectangle_lines(panel_x, panel_y, panel_width, log_height, 1.0, GRAY);
+        for (i, line) in self.chat_log.iter().enumerate() {
+            draw_text(line, panel_x + 6.0, panel_y + (i as f32 + 1.0) * line_height - 4.0, 16.0, WHITE);
+        }
+
+        let input_y = panel_y + log_height + 4.0;
+        let input_height = 24.0;
+        draw_rectangle(panel_x, input_y, panel_width, input_height, Color::from_rgba(30, 30, 50, 220));
+        draw_rectangle_lines(panel_x, input_y, panel_width, input_height, 1.0, WHITE);
+        let input_text = if self.chat_active {
+            format!("{}_", self.chat_input)
+        } else {
+            "Press Enter to chat".to_string()
+        };
+        let input_color = if self.chat_active { WHITE } else { GRAY };
+        draw_text(&input_text, panel_x + 6.0, input_y + 17.0, 16.0, input_color);
+    }
+
+    /// Draws one board's gems at `(offset_x, offset_y)`. Shared by the
+    /// player's own grid and the opponent/spectated grid so both render
+    /// identically; `selected` is only meaningful for the player's own board.
+    fn draw_board(
+        &self,
+        grid: &[Vec<Option<Gem>>],
+        offset_x: f32,
+        offset_y: f32,
+        selected: Option<(usize, usize)>,
+    ) {
         draw_rectangle(
-            BOARD_OFFSET_X - 10.0 + shake_x,
-            BOARD_OFFSET_Y - 10.0 + shake_y,
+            offset_x - 10.0,
+            offset_y - 10.0,
             GRID_SIZE as f32 * GEM_SIZE + 20.0,
             GRID_SIZE as f32 * GEM_SIZE + 20.0,
             Color::from_rgba(40, 40, 70, 255),
         );
 
-        // Gems
         for row in 0..GRID_SIZE {
             for col in 0..GRID_SIZE {
-                let x = BOARD_OFFSET_X + col as f32 * GEM_SIZE + shake_x;
-                let y = BOARD_OFFSET_Y + row as f32 * GEM_SIZE + shake_y;
+                let x = offset_x + col as f32 * GEM_SIZE;
+                let y = offset_y + row as f32 * GEM_SIZE;
 
                 draw_rectangle(
                     x + 2.0, y + 2.0,
@@ -1680,7 +3172,7 @@ impl Game {
                     Color::from_rgba(30, 30, 50, 255),
                 );
 
-                if let Some(gem) = self.grid[row][col] {
+                if let Some(gem) = grid[row][col] {
                     let gem_y = y + gem.y_offset;
 
                     // Draw gem based on type
@@ -1738,7 +3230,7 @@ impl Game {
                 }
 
                 // Selection highlight
-                if let Some((sel_row, sel_col)) = self.selected {
+                if let Some((sel_row, sel_col)) = selected {
                     if sel_row == row && sel_col == col {
                         draw_rectangle_lines(x, y, GEM_SIZE, GEM_SIZE, 4.0, YELLOW);
                     }
@@ -1747,6 +3239,42 @@ impl Game {
         }
     }
 
+    /// Renders `self.particles` at `(offset_x, offset_y)` — the same board
+    /// origin `draw_board` was just called with, so effects line up with
+    /// the gems they were spawned against.
+    fn draw_particles(&self, offset_x: f32, offset_y: f32) {
+        for particle in &self.particles {
+            let alpha = particle.alpha();
+            match &particle.kind {
+                ParticleKind::Spark { color } => {
+                    let c = Color::new(color.r, color.g, color.b, alpha);
+                    let radius = 3.0 + (particle.anim_counter * 20.0).sin().abs() * 2.0;
+                    draw_circle(offset_x + particle.x, offset_y + particle.y, radius, c);
+                }
+                ParticleKind::DrillBeamRow(row) => {
+                    let y = offset_y + *row as f32 * GEM_SIZE + GEM_SIZE / 2.0;
+                    let c = Color::new(1.0, 1.0, 1.0, alpha);
+                    draw_line(offset_x, y, offset_x + GRID_SIZE as f32 * GEM_SIZE, y, 6.0, c);
+                }
+                ParticleKind::DrillBeamCol(col) => {
+                    let x = offset_x + *col as f32 * GEM_SIZE + GEM_SIZE / 2.0;
+                    let c = Color::new(1.0, 1.0, 1.0, alpha);
+                    draw_line(x, offset_y, x, offset_y + GRID_SIZE as f32 * GEM_SIZE, 6.0, c);
+                }
+                ParticleKind::Explosion { color } => {
+                    let progress = 1.0 - particle.lifetime / particle.max_lifetime;
+                    let radius = GEM_SIZE * 0.3 + GEM_SIZE * progress;
+                    let c = Color::new(color.r, color.g, color.b, alpha);
+                    draw_circle_lines(offset_x + particle.x, offset_y + particle.y, radius, 4.0, c);
+                }
+                ParticleKind::FloatingText { text, color } => {
+                    let c = Color::new(color.r, color.g, color.b, alpha);
+                    draw_text(text, offset_x + particle.x, offset_y + particle.y, 24.0, c);
+                }
+            }
+        }
+    }
+
     fn draw_game_over(&self) {
         self.draw_game();
 
@@ -1873,6 +3401,44 @@ impl Game {
             if button_text.len() > 15 { 20.0 } else { 30.0 },
             WHITE
         );
+
+        // Lets a player who finished early (e.g. an overflow loss) keep
+        // watching their own match play out via BoardSnapshot broadcasts.
+        if self.network_mode == NetworkMode::Online && self.current_game_id.is_some() {
+            let spectate_y = button_y + 60.0;
+            draw_rectangle(button_x, spectate_y, 200.0, 40.0, Color::from_rgba(80, 100, 180, 255));
+            draw_text("SPECTATE MATCH", button_x + 15.0, spectate_y + 27.0, 20.0, WHITE);
+        }
+
+        // Only an offline match's replay_log is meaningful to save - an
+        // online one is keyed to a server seed the opponent also consumed.
+        if self.network_mode == NetworkMode::Offline {
+            let save_replay_y = button_y + 60.0;
+            draw_rectangle(button_x, save_replay_y, 200.0, 40.0, Color::from_rgba(180, 140, 60, 255));
+            draw_text("SAVE REPLAY", button_x + 25.0, save_replay_y + 27.0, 20.0, WHITE);
+        }
+    }
+
+    fn draw_spectating(&self) {
+        let screen_width = screen_width();
+
+        draw_rectangle(0.0, 0.0, screen_width, 130.0, Color::from_rgba(30, 30, 60, 255));
+        draw_text("SPECTATING", 20.0, 40.0, 35.0, LIGHTGRAY);
+        draw_text(
+            &format!("Score: {}  Energy: {}", self.opponent_score, self.opponent_energy),
+            20.0, 75.0, 25.0, YELLOW,
+        );
+
+        if let Some(grid) = &self.opponent_grid {
+            self.draw_board(grid, BOARD_OFFSET_X, BOARD_OFFSET_Y, None);
+        } else {
+            draw_text("Waiting for board data...", BOARD_OFFSET_X, BOARD_OFFSET_Y + 40.0, 25.0, LIGHTGRAY);
+        }
+
+        let button_x = screen_width / 2.0 - 100.0;
+        let button_y = screen_height() - 80.0;
+        draw_rectangle(button_x, button_y, 200.0, 40.0, Color::from_rgba(100, 100, 100, 255));
+        draw_text("BACK TO MENU", button_x + 15.0, button_y + 27.0, 20.0, WHITE);
     }
 
     fn draw_leaderboard(&self) {
@@ -1907,7 +3473,7 @@ impl Game {
                 GRAY,
             );
         } else {
-            for (i, (username, elo)) in self.leaderboard_data.iter().enumerate() {
+            for (i, (username, rating)) in self.leaderboard_data.iter().enumerate() {
                 let y = start_y + i as f32 * row_height;
 
                 // Background for each row
@@ -1948,9 +3514,9 @@ impl Game {
                     WHITE,
                 );
 
-                // ELO
+                // Rating
                 draw_text(
-                    &format!("{} ELO", elo),
+                    &format!("{:.0}", rating),
                     screen_width / 2.0 + 100.0,
                     y,
                     28.0,
@@ -1959,6 +3525,64 @@ impl Game {
             }
         }
 
+        // Live matches - pick one to spectate
+        draw_text("LIVE MATCHES - CLICK TO WATCH", screen_width / 2.0 - 220.0, 635.0, 22.0, LIGHTGRAY);
+        if self.match_list.is_empty() {
+            draw_text("No matches in progress.", screen_width / 2.0 - 150.0, 665.0, 20.0, GRAY);
+        } else {
+            for (i, (_, name1, name2)) in self.match_list.iter().enumerate() {
+                let (x, y, w, h) = live_match_row_rect(i);
+                draw_rectangle(x, y, w, h, Color::from_rgba(50, 50, 85, 255));
+                draw_text(&format!("{} vs {}", name1, name2), x + 15.0, y + 27.0, 22.0, WHITE);
+            }
+        }
+
+        // Back button
+        let back_x = screen_width / 2.0 - 100.0;
+        let back_y = screen_height - 100.0;
+        draw_rectangle(back_x, back_y, 200.0, 50.0, Color::from_rgba(100, 100, 100, 255));
+        draw_text("BACK TO MENU", back_x + 25.0, back_y + 33.0, 25.0, WHITE);
+    }
+
+    fn draw_server_browser(&self) {
+        let screen_width = screen_width();
+        let screen_height = screen_height();
+
+        draw_text(
+            "SELECT A SERVER",
+            screen_width / 2.0 - 150.0,
+            80.0,
+            40.0,
+            WHITE,
+        );
+
+        for (i, entry) in self.server_list.iter().enumerate() {
+            let (x, y, w, h) = server_browser_row_rect(i);
+
+            let bg_color = if i % 2 == 0 {
+                Color::from_rgba(40, 40, 70, 255)
+            } else {
+                Color::from_rgba(30, 30, 60, 255)
+            };
+            draw_rectangle(x, y, w, h, bg_color);
+
+            draw_text(&entry.name, x + 20.0, y + 35.0, 28.0, WHITE);
+
+            let status_text = match entry.status {
+                ServerStatus::Pending => "checking...".to_string(),
+                ServerStatus::Online { players_online, queue_size } => {
+                    format!("{} online, {} in queue", players_online, queue_size)
+                }
+                ServerStatus::Unreachable => "unreachable".to_string(),
+            };
+            let status_color = match entry.status {
+                ServerStatus::Pending => GRAY,
+                ServerStatus::Online { .. } => Color::from_rgba(120, 220, 120, 255),
+                ServerStatus::Unreachable => Color::from_rgba(220, 100, 100, 255),
+            };
+            draw_text(&status_text, x + w - 260.0, y + 35.0, 22.0, status_color);
+        }
+
         // Back button
         let back_x = screen_width / 2.0 - 100.0;
         let back_y = screen_height - 100.0;
@@ -1982,13 +3606,199 @@ impl MatchType {
     }
 }
 
+/// Position (`x, y, w, h`) of the `index`-th row on the `ServerBrowser`
+/// screen, shared between `draw_server_browser` and the click handler in
+/// `main`.
+fn server_browser_row_rect(index: usize) -> (f32, f32, f32, f32) {
+    const ROW_WIDTH: f32 = 500.0;
+    const ROW_HEIGHT: f32 = 50.0;
+    const ROW_SPACING: f32 = 60.0;
+    let x = screen_width() / 2.0 - ROW_WIDTH / 2.0;
+    let y = 150.0 + index as f32 * ROW_SPACING;
+    (x, y, ROW_WIDTH, ROW_HEIGHT)
+}
+
+fn live_match_row_rect(index: usize) -> (f32, f32, f32, f32) {
+    const ROW_WIDTH: f32 = 500.0;
+    const ROW_HEIGHT: f32 = 40.0;
+    const ROW_SPACING: f32 = 45.0;
+    let x = screen_width() / 2.0 - ROW_WIDTH / 2.0;
+    let y = 660.0 + index as f32 * ROW_SPACING;
+    (x, y, ROW_WIDTH, ROW_HEIGHT)
+}
+
+/// Position (`x, y, w, h`) of the `index`-th box in the in-match emote bar
+/// (`Emote::ALL` order), shared between `Game::draw_game`'s rendering and
+/// the click handler in `main`.
+fn emote_bar_rect(index: usize) -> (f32, f32, f32, f32) {
+    const EMOTE_BOX_WIDTH: f32 = 55.0;
+    const EMOTE_BOX_HEIGHT: f32 = 26.0;
+    const EMOTE_BOX_SPACING: f32 = 60.0;
+    let x = 20.0 + index as f32 * EMOTE_BOX_SPACING;
+    let y = 125.0;
+    (x, y, EMOTE_BOX_WIDTH, EMOTE_BOX_HEIGHT)
+}
+
+/// Flattens a grid into the row-major `Vec<u8>` wire format used by
+/// `BoardUpdate`/`BoardSnapshot`, one gem-type byte per cell and `0xFF` for
+/// an empty cell.
+fn encode_board(grid: &[Vec<Option<Gem>>]) -> Vec<u8> {
+    grid.iter()
+        .flat_map(|row| row.iter())
+        .map(|cell| cell.map(|gem| gem.gem_type.to_wire_id()).unwrap_or(0xFF))
+        .collect()
+}
+
+/// Inverse of `encode_board`; unrecognized bytes decode to an empty cell.
+/// Returns `None` if `bytes` isn't exactly `GRID_SIZE * GRID_SIZE` long, so
+/// a truncated or malformed payload gets dropped here instead of trusted
+/// through to `draw_board`'s unconditional `grid[row][col]` indexing.
+fn decode_board(bytes: &[u8]) -> Option<Vec<Vec<Option<Gem>>>> {
+    if bytes.len() != GRID_SIZE * GRID_SIZE {
+        return None;
+    }
+    Some(
+        bytes
+            .chunks(GRID_SIZE)
+            .map(|row| {
+                row.iter()
+                    .map(|&b| GemType::from_wire_id(b).map(Gem::new))
+                    .collect()
+            })
+            .collect(),
+    )
+}
+
 // Async function to connect to WebSocket server
-async fn connect_to_server() -> Option<NetworkBridge> {
-    let url = "ws://127.0.0.1:9001";
+/// How to validate the server's TLS certificate.
+#[derive(Clone)]
+enum TlsPinning {
+    /// Validate against the system's trusted CA roots - the path for a
+    /// production server behind a Let's-Encrypt-style certificate.
+    SystemRoots,
+    /// Accept only a server whose leaf certificate's SHA-256 fingerprint
+    /// matches exactly, for self-hosted servers running their own CA-less
+    /// self-signed cert.
+    PinnedFingerprint([u8; 32]),
+}
+
+/// Where to connect and how to trust what's on the other end. Built once at
+/// startup and threaded down to wherever the network task is spawned.
+#[derive(Clone)]
+struct NetworkConfig {
+    url: String,
+    tls_pinning: TlsPinning,
+}
+
+impl NetworkConfig {
+    /// The default production target: `wss://` with system trust roots.
+    fn default_server() -> Self {
+        Self {
+            url: "wss://127.0.0.1:9001".to_string(),
+            tls_pinning: TlsPinning::SystemRoots,
+        }
+    }
+}
+
+/// Servers offered on the `ServerBrowser` screen. A real deployment would
+/// likely fetch this list remotely; a short in-crate list is enough for the
+/// handful of servers this game ever points at.
+const KNOWN_SERVERS: &[(&str, &str)] = &[("Local", "wss://127.0.0.1:9001")];
+
+/// Result of status-pinging one `ServerBrowserEntry`, shown alongside its
+/// name on the `ServerBrowser` screen.
+#[derive(Clone)]
+enum ServerStatus {
+    Pending,
+    Online { players_online: usize, queue_size: usize },
+    Unreachable,
+}
+
+/// One row on the `ServerBrowser` screen: a known server plus its last
+/// status-ping result.
+#[derive(Clone)]
+struct ServerBrowserEntry {
+    name: String,
+    url: String,
+    status: ServerStatus,
+}
+
+/// Accepts a server certificate only if its SHA-256 fingerprint matches the
+/// one pinned in `NetworkConfig`, bypassing normal chain-of-trust validation
+/// entirely. Only ever installed for `TlsPinning::PinnedFingerprint`.
+struct FingerprintVerifier {
+    fingerprint: [u8; 32],
+}
 
-    println!("Attempting to connect to server at {}...", url);
+impl rustls::client::ServerCertVerifier for FingerprintVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &rustls::Certificate,
+        _intermediates: &[rustls::Certificate],
+        _server_name: &rustls::ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: std::time::SystemTime,
+    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
+        let actual: [u8; 32] = ::sha2::Sha256::digest(&end_entity.0).into();
+        if actual == self.fingerprint {
+            Ok(rustls::client::ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::General("TLS certificate fingerprint mismatch".to_string()))
+        }
+    }
+}
+
+/// Builds the rustls connector for `connect_async_tls_with_config` matching
+/// the configured trust mode.
+fn tls_connector(pinning: &TlsPinning) -> tokio_tungstenite::Connector {
+    let config = match pinning {
+        TlsPinning::SystemRoots => {
+            let mut roots = rustls::RootCertStore::empty();
+            roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|ta| {
+                rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(
+                    ta.subject, ta.spki, ta.name_constraints,
+                )
+            }));
+            rustls::ClientConfig::builder()
+                .with_safe_defaults()
+                .with_root_certificates(roots)
+                .with_no_client_auth()
+        }
+        TlsPinning::PinnedFingerprint(fingerprint) => {
+            let mut config = rustls::ClientConfig::builder()
+                .with_safe_defaults()
+                .with_root_certificates(rustls::RootCertStore::empty())
+                .with_no_client_auth();
+            config.dangerous().set_certificate_verifier(Arc::new(FingerprintVerifier {
+                fingerprint: *fingerprint,
+            }));
+            config
+        }
+    };
+    tokio_tungstenite::Connector::Rustls(Arc::new(config))
+}
 
-    match connect_async(url).await {
+/// Dials `config.url` over whichever transport this build was compiled
+/// with: reliable UDP when the `udp-transport` feature is on (see
+/// `udp_transport::connect_udp`), otherwise the default TCP WebSocket path
+/// below. Both return the same `NetworkBridge`, so callers never branch on
+/// transport themselves.
+async fn connect_to_server(config: &NetworkConfig) -> Result<NetworkBridge, NetError> {
+    #[cfg(feature = "udp-transport")]
+    {
+        return udp_transport::connect_udp(config).await;
+    }
+    #[cfg(not(feature = "udp-transport"))]
+    connect_to_server_ws(config).await
+}
+
+#[cfg(not(feature = "udp-transport"))]
+async fn connect_to_server_ws(config: &NetworkConfig) -> Result<NetworkBridge, NetError> {
+    println!("Attempting to connect to server at {}...", config.url);
+
+    let connector = tls_connector(&config.tls_pinning);
+    match tokio_tungstenite::connect_async_tls_with_config(&config.url, None, false, Some(connector)).await {
         Ok((ws_stream, _)) => {
             println!("Connected to server!");
 
@@ -1997,6 +3807,7 @@ async fn connect_to_server() -> Option<NetworkBridge> {
             // Create channels
             let (to_server_tx, mut to_server_rx) = unbounded_channel::<ClientMessage>();
             let (from_server_tx, from_server_rx) = unbounded_channel::<ServerMessage>();
+            let (error_tx, error_rx) = unbounded_channel::<NetError>();
 
             // Spawn task to handle WebSocket communication
             tokio::spawn(async move {
@@ -2007,10 +3818,21 @@ async fn connect_to_server() -> Option<NetworkBridge> {
                     tokio::select! {
                         // Receive from game and send to server
                         Some(msg) = to_server_rx.recv() => {
-                            let json = serde_json::to_string(&msg).unwrap();
-                            if write.send(Message::Text(json)).await.is_err() {
-                                println!("Failed to send message to server");
-                                break;
+                            match serde_json::to_string(&msg) {
+                                Ok(json) => {
+                                    if write.send(Message::Text(json)).await.is_err() {
+                                        println!("Failed to send message to server");
+                                        let _ = error_tx.send(NetError::Closed);
+                                        break;
+                                    }
+                                }
+                                Err(e) => {
+                                    // A single bad message shouldn't take the
+                                    // whole connection down - report it and
+                                    // keep serving the rest of the queue.
+                                    println!("Failed to serialize outgoing message: {}", e);
+                                    let _ = error_tx.send(NetError::Serialize(e.to_string()));
+                                }
                             }
                         }
 
@@ -2027,10 +3849,12 @@ async fn connect_to_server() -> Option<NetworkBridge> {
                                 }
                                 Some(Ok(Message::Close(_))) | None => {
                                     println!("Server disconnected");
+                                    let _ = error_tx.send(NetError::Closed);
                                     break;
                                 }
                                 Some(Err(e)) => {
                                     println!("WebSocket error: {}", e);
+                                    let _ = error_tx.send(NetError::Closed);
                                     break;
                                 }
                                 _ => {}
@@ -2040,18 +3864,60 @@ async fn connect_to_server() -> Option<NetworkBridge> {
                 }
             });
 
-            Some(NetworkBridge {
+            Ok(NetworkBridge {
                 to_server: to_server_tx,
                 from_server: from_server_rx,
+                errors: error_rx,
             })
         }
         Err(e) => {
             println!("Failed to connect to server: {}", e);
-            None
+            Err(NetError::ConnectFailed(e.to_string()))
         }
     }
 }
 
+/// A lightweight, join-free status ping for the `ServerBrowser` screen:
+/// completes the `Hello` handshake, asks for `RequestServerStatus`, and
+/// reports the counts back without ever sending `Login`.
+async fn fetch_server_status(config: &NetworkConfig) -> Result<(usize, usize), NetError> {
+    let connector = tls_connector(&config.tls_pinning);
+    let (mut ws_stream, _) =
+        tokio_tungstenite::connect_async_tls_with_config(&config.url, None, false, Some(connector))
+            .await
+            .map_err(|e| NetError::ConnectFailed(e.to_string()))?;
+
+    let hello = ClientMessage::Hello {
+        protocol_version: PROTOCOL_VERSION,
+        client_build: CLIENT_BUILD.to_string(),
+    };
+    ws_stream
+        .send(Message::Text(serde_json::to_string(&hello).unwrap()))
+        .await
+        .map_err(|_| NetError::Closed)?;
+
+    match ws_stream.next().await {
+        Some(Ok(Message::Text(text))) => match serde_json::from_str::<ServerMessage>(&text) {
+            Ok(ServerMessage::HelloAck { accepted: true, .. }) => {}
+            _ => return Err(NetError::Closed),
+        },
+        _ => return Err(NetError::Closed),
+    }
+
+    ws_stream
+        .send(Message::Text(serde_json::to_string(&ClientMessage::RequestServerStatus).unwrap()))
+        .await
+        .map_err(|_| NetError::Closed)?;
+
+    match ws_stream.next().await {
+        Some(Ok(Message::Text(text))) => match serde_json::from_str::<ServerMessage>(&text) {
+            Ok(ServerMessage::ServerStatusReport { players_online, queue_size }) => Ok((players_online, queue_size)),
+            _ => Err(NetError::Closed),
+        },
+        _ => Err(NetError::Closed),
+    }
+}
+
 fn window_conf() -> Conf {
     Conf {
         window_title: "Brick City Wars - Match3 PVP".to_owned(),
@@ -2073,24 +3939,61 @@ async fn main() {
         if game.state == GameState::Connecting && !connecting {
             connecting = true;
             // Attempt to connect to server
-            if let Some(bridge) = connect_to_server().await {
-                game.set_network_bridge(bridge);
-                // Send Login message immediately after connecting
-                if let Some(network_bridge) = &game.network_bridge {
-                    network_bridge.send(ClientMessage::Login {
-                        username: game.username.clone(),
-                    });
+            match connect_to_server(&game.network_config).await {
+                Ok(bridge) => {
+                    game.set_network_bridge(bridge);
+                    // Login follows once HelloAck accepts the handshake; see
+                    // handle_server_message.
+                }
+                Err(e) => {
+                    println!("Failed to connect - falling back to offline mode");
+                    game.network_mode = NetworkMode::Offline;
+                    game.state = GameState::Login;
+                    game.disconnect_reason = Some(format!("Connection failed: {}", e));
                 }
-                // Wait for AuthAccepted/AuthRejected in handle_server_message
-            } else {
-                println!("Failed to connect - falling back to offline mode");
-                game.network_mode = NetworkMode::Offline;
-                game.state = GameState::Login;
-                game.disconnect_reason = Some("Connection failed".to_string());
             }
             connecting = false;
         }
 
+        // Handle reconnect attempts (exponential backoff between tries)
+        if game.state == GameState::Reconnecting && !connecting && game.reconnect_timer <= 0.0 {
+            connecting = true;
+            match connect_to_server(&game.network_config).await {
+                Ok(bridge) => {
+                    game.set_network_bridge(bridge);
+                    // Resume follows once HelloAck accepts the handshake; see
+                    // handle_server_message.
+                }
+                Err(e) => {
+                    println!("Reconnect attempt failed: {}", e);
+                    game.disconnect_reason = Some(format!("Connection lost - reconnecting... ({})", e));
+                    game.reconnect_backoff = (game.reconnect_backoff * 2.0).min(RECONNECT_MAX_BACKOFF);
+                    game.reconnect_timer = game.reconnect_backoff;
+                }
+            }
+            connecting = false;
+        }
+
+        // Refresh the server browser's live player/queue counts. Each known
+        // server is status-pinged in turn (see `fetch_server_status`); this
+        // blocks rendering for the few frames it takes, same as connecting.
+        if game.state == GameState::ServerBrowser && game.server_browser_needs_refresh {
+            game.server_browser_needs_refresh = false;
+            for entry in game.server_list.iter_mut() {
+                entry.status = ServerStatus::Pending;
+            }
+            for i in 0..game.server_list.len() {
+                let config = NetworkConfig {
+                    url: game.server_list[i].url.clone(),
+                    tls_pinning: game.network_config.tls_pinning.clone(),
+                };
+                game.server_list[i].status = match fetch_server_status(&config).await {
+                    Ok((players_online, queue_size)) => ServerStatus::Online { players_online, queue_size },
+                    Err(_) => ServerStatus::Unreachable,
+                };
+            }
+        }
+
         game.update(dt);
 
         if is_mouse_button_pressed(MouseButton::Left) {
@@ -2114,8 +4017,8 @@ async fn main() {
                     let online_y = screen_height / 2.0 + 50.0;
                     if mouse_x >= online_x && mouse_x <= online_x + 200.0
                         && mouse_y >= online_y && mouse_y <= online_y + 50.0 {
-                        game.state = GameState::Login;
-                        game.pending_username.clear();
+                        game.state = GameState::ServerBrowser;
+                        game.server_browser_needs_refresh = true;
                         game.disconnect_reason = None;
                     }
 
@@ -2125,15 +4028,57 @@ async fn main() {
                     if mouse_x >= leaderboard_x && mouse_x <= leaderboard_x + 200.0
                         && mouse_y >= leaderboard_y && mouse_y <= leaderboard_y + 50.0 {
                         game.connecting_for_leaderboard = true;
-                        game.state = GameState::Login;
-                        game.pending_username.clear();
+                        game.state = GameState::ServerBrowser;
+                        game.server_browser_needs_refresh = true;
                         game.disconnect_reason = None;
                     }
+
+                    // Load replay button
+                    let load_replay_x = screen_width / 2.0 - 100.0;
+                    let load_replay_y = screen_height / 2.0 + 190.0;
+                    if mouse_x >= load_replay_x && mouse_x <= load_replay_x + 200.0
+                        && mouse_y >= load_replay_y && mouse_y <= load_replay_y + 50.0 {
+                        game.load_replay();
+                    }
+                }
+                GameState::ServerBrowser => {
+                    let screen_height = screen_height();
+
+                    for (i, entry) in game.server_list.iter().enumerate() {
+                        let (x, y, w, h) = server_browser_row_rect(i);
+                        if mouse_x >= x && mouse_x <= x + w && mouse_y >= y && mouse_y <= y + h {
+                            game.network_config.url = entry.url.clone();
+                            game.state = GameState::Login;
+                            game.pending_username.clear();
+                            game.pending_password.clear();
+                            game.login_focus = LoginField::Username;
+                            game.register_mode = false;
+                            game.disconnect_reason = None;
+                        }
+                    }
+
+                    // Back button
+                    let back_x = screen_width() / 2.0 - 100.0;
+                    let back_y = screen_height - 100.0;
+                    if mouse_x >= back_x && mouse_x <= back_x + 200.0
+                        && mouse_y >= back_y && mouse_y <= back_y + 50.0 {
+                        game.state = GameState::Menu;
+                        game.connecting_for_leaderboard = false;
+                    }
                 }
                 GameState::Leaderboard => {
                     let screen_width = screen_width();
                     let screen_height = screen_height();
 
+                    // Live match rows - click one to spectate it
+                    for (i, (game_id, _, _)) in game.match_list.clone().iter().enumerate() {
+                        let (x, y, w, h) = live_match_row_rect(i);
+                        if mouse_x >= x && mouse_x <= x + w && mouse_y >= y && mouse_y <= y + h {
+                            game.send_to_server(ClientMessage::SpectateGame { game_id: *game_id });
+                            game.state = GameState::Spectating;
+                        }
+                    }
+
                     // Back button
                     let back_x = screen_width / 2.0 - 100.0;
                     let back_y = screen_height - 100.0;
@@ -2146,32 +4091,74 @@ async fn main() {
                     let screen_width = screen_width();
                     let screen_height = screen_height();
 
-                    // Continue button
+                    // Username input box - click to focus
+                    let input_x = screen_width / 2.0 - 150.0;
+                    let input_y = screen_height / 2.0 - 30.0;
+                    if mouse_x >= input_x && mouse_x <= input_x + 300.0
+                        && mouse_y >= input_y && mouse_y <= input_y + 50.0 {
+                        game.login_focus = LoginField::Username;
+                    }
+
+                    // Password input box - click to focus
+                    let password_x = input_x;
+                    let password_y = screen_height / 2.0 + 30.0;
+                    if mouse_x >= password_x && mouse_x <= password_x + 300.0
+                        && mouse_y >= password_y && mouse_y <= password_y + 45.0 {
+                        game.login_focus = LoginField::Password;
+                    }
+
+                    // Server address / room code input box - click to focus
+                    let target_x = input_x;
+                    let target_y = screen_height / 2.0 + 85.0;
+                    if mouse_x >= target_x && mouse_x <= target_x + 300.0
+                        && mouse_y >= target_y && mouse_y <= target_y + 45.0 {
+                        game.login_focus = LoginField::ServerTarget;
+                    }
+
+                    // Login/Register mode toggle
+                    let toggle_x = screen_width / 2.0 - 150.0;
+                    let toggle_y = screen_height / 2.0 + 140.0;
+                    if mouse_x >= toggle_x && mouse_x <= toggle_x + 300.0
+                        && mouse_y >= toggle_y && mouse_y <= toggle_y + 20.0 {
+                        game.register_mode = !game.register_mode;
+                    }
+
+                    // Continue / Register button
                     let button_x = screen_width / 2.0 - 100.0;
-                    let button_y = screen_height / 2.0 + 50.0;
+                    let button_y = screen_height / 2.0 + 170.0;
                     if !game.pending_username.is_empty()
+                        && !game.pending_password.is_empty()
                         && mouse_x >= button_x && mouse_x <= button_x + 200.0
                         && mouse_y >= button_y && mouse_y <= button_y + 50.0 {
-                        // Submit username and transition to Connecting
-                        game.username = game.pending_username.clone();
-                        game.state = GameState::Connecting;
-                        game.network_mode = NetworkMode::Online;
+                        game.submit_login();
                     }
 
                     // Back button
                     let back_x = screen_width / 2.0 - 100.0;
-                    let back_y = screen_width / 2.0 + 170.0;
+                    let back_y = screen_height / 2.0 + 290.0;
                     if mouse_x >= back_x && mouse_x <= back_x + 200.0
                         && mouse_y >= back_y && mouse_y <= back_y + 40.0 {
                         game.state = GameState::Menu;
                         game.pending_username.clear();
+                        game.pending_password.clear();
+                        game.connect_target_input.clear();
+                        game.login_focus = LoginField::Username;
+                        game.register_mode = false;
                     }
                 }
                 GameState::Connecting => {
                     // Waiting for connection - handled by async task
                 }
                 GameState::Playing => {
-                    game.handle_click(mouse_x, mouse_y);
+                    let clicked_emote = Emote::ALL.iter().enumerate().find_map(|(i, emote)| {
+                        let (ex, ey, ew, eh) = emote_bar_rect(i);
+                        (mouse_x >= ex && mouse_x <= ex + ew && mouse_y >= ey && mouse_y <= ey + eh)
+                            .then_some(*emote)
+                    });
+                    match clicked_emote {
+                        Some(emote) => game.send_emote(emote),
+                        None => game.handle_click(mouse_x, mouse_y),
+                    }
                 }
                 GameState::GameOver => {
                     let screen_width = screen_width();
@@ -2185,52 +4172,154 @@ async fn main() {
                             // Request rematch in online mode
                             if !game.requested_rematch {
                                 game.requested_rematch = true;
-                                if let Some(bridge) = &game.network_bridge {
-                                    bridge.send(ClientMessage::RequestRematch);
-                                }
+                                game.send_to_server(ClientMessage::RequestRematch);
                             }
                         } else {
                             // Go back to menu in offline mode
                             game.state = GameState::Menu;
                         }
                     }
+
+                    // Spectate button - only shown/clickable if we still
+                    // know a game_id (online match) that may be ongoing
+                    let spectate_y = button_y + 60.0;
+                    if game.network_mode == NetworkMode::Online
+                        && game.current_game_id.is_some()
+                        && mouse_x >= button_x && mouse_x <= button_x + 200.0
+                        && mouse_y >= spectate_y && mouse_y <= spectate_y + 40.0 {
+                        if let Some(game_id) = game.current_game_id {
+                            game.send_to_server(ClientMessage::SpectateGame { game_id });
+                            game.state = GameState::Spectating;
+                        }
+                    }
+
+                    // Save replay button (offline matches only)
+                    let save_replay_y = button_y + 60.0;
+                    if game.network_mode == NetworkMode::Offline
+                        && mouse_x >= button_x && mouse_x <= button_x + 200.0
+                        && mouse_y >= save_replay_y && mouse_y <= save_replay_y + 40.0 {
+                        game.save_replay();
+                    }
+                }
+                GameState::Spectating => {
+                    let screen_width = screen_width();
+                    let screen_height = screen_height();
+                    let button_x = screen_width / 2.0 - 100.0;
+                    let button_y = screen_height - 80.0;
+
+                    if mouse_x >= button_x && mouse_x <= button_x + 200.0
+                        && mouse_y >= button_y && mouse_y <= button_y + 40.0 {
+                        game.state = GameState::Menu;
+                    }
                 }
                 _ => {}
             }
         }
 
-        // Handle keyboard input for login screen
+        // Handle keyboard input for login screen. Tab cycles which of the
+        // three fields (username, password, server address) keyboard input
+        // routes to.
         if game.state == GameState::Login {
-            // Get character input
+            if is_key_pressed(KeyCode::Tab) {
+                game.login_focus = match game.login_focus {
+                    LoginField::Username => LoginField::Password,
+                    LoginField::Password => LoginField::ServerTarget,
+                    LoginField::ServerTarget => LoginField::Username,
+                };
+            }
+            let field = match game.login_focus {
+                LoginField::Username => &mut game.pending_username,
+                LoginField::Password => &mut game.pending_password,
+                LoginField::ServerTarget => &mut game.connect_target_input,
+            };
+
+            // Get character input. The server-address field additionally
+            // allows '.' and ':' for host:port addresses; the password field
+            // accepts any printable character instead of just alnum/_/-.
             if let Some(character) = get_char_pressed() {
-                if character.is_alphanumeric() || character == '_' || character == '-' {
-                    if game.pending_username.len() < 20 {
-                        game.pending_username.push(character);
+                let allowed = match game.login_focus {
+                    LoginField::Password => !character.is_control(),
+                    LoginField::ServerTarget => {
+                        character.is_alphanumeric() || character == '_' || character == '-'
+                            || character == '.' || character == ':'
                     }
+                    LoginField::Username => {
+                        character.is_alphanumeric() || character == '_' || character == '-'
+                    }
+                };
+                if allowed {
+                    field.insert(character);
                 }
             }
 
-            // Handle backspace
+            // Handle backspace / delete
             if is_key_pressed(KeyCode::Backspace) {
-                game.pending_username.pop();
+                field.backspace();
+            }
+            if is_key_pressed(KeyCode::Delete) {
+                field.delete_forward();
+            }
+
+            // Handle cursor movement
+            if is_key_pressed(KeyCode::Left) {
+                field.move_left();
+            }
+            if is_key_pressed(KeyCode::Right) {
+                field.move_right();
+            }
+            if is_key_pressed(KeyCode::Home) {
+                field.move_home();
+            }
+            if is_key_pressed(KeyCode::End) {
+                field.move_end();
             }
 
             // Handle enter key to submit
-            if is_key_pressed(KeyCode::Enter) && !game.pending_username.is_empty() {
-                game.username = game.pending_username.clone();
-                game.state = GameState::Connecting;
-                game.network_mode = NetworkMode::Online;
+            if is_key_pressed(KeyCode::Enter) && !game.pending_username.is_empty() && !game.pending_password.is_empty() {
+                game.submit_login();
             }
 
             // Handle escape to go back
             if is_key_pressed(KeyCode::Escape) {
                 game.state = GameState::Menu;
                 game.pending_username.clear();
+                game.pending_password.clear();
+                game.connect_target_input.clear();
+                game.login_focus = LoginField::Username;
+                game.register_mode = false;
+            }
+        }
+
+        // Handle keyboard input for the in-match chat box. Reuses the same
+        // typing pattern as the login screen above, but toggled by Enter
+        // instead of always capturing input, since Playing also binds keys
+        // to boosters/emotes that typing shouldn't trigger.
+        if game.state == GameState::Playing && game.chat_active {
+            if let Some(character) = get_char_pressed() {
+                if !character.is_control() && game.chat_input.len() < 120 {
+                    game.chat_input.push(character);
+                }
+            }
+
+            if is_key_pressed(KeyCode::Backspace) {
+                game.chat_input.pop();
+            }
+
+            if is_key_pressed(KeyCode::Enter) {
+                game.send_chat();
+                game.chat_active = false;
             }
+
+            if is_key_pressed(KeyCode::Escape) {
+                game.chat_input.clear();
+                game.chat_active = false;
+            }
+        } else if game.state == GameState::Playing && is_key_pressed(KeyCode::Enter) {
+            game.chat_active = true;
         }
 
         // Handle keyboard input for boosters (keys 1, 2, 3)
-        if game.state == GameState::Playing {
+        if game.state == GameState::Playing && !game.chat_active {
             if is_key_pressed(KeyCode::Key1) {
                 game.activate_booster(0);
             }
@@ -2240,6 +4329,20 @@ async fn main() {
             if is_key_pressed(KeyCode::Key3) {
                 game.activate_booster(2);
             }
+
+            // Emotes (F1-F4, kept off 1/2/3 which are bound to boosters)
+            if is_key_pressed(KeyCode::F1) {
+                game.send_emote(Emote::Gg);
+            }
+            if is_key_pressed(KeyCode::F2) {
+                game.send_emote(Emote::Nice);
+            }
+            if is_key_pressed(KeyCode::F3) {
+                game.send_emote(Emote::Oops);
+            }
+            if is_key_pressed(KeyCode::F4) {
+                game.send_emote(Emote::Angry);
+            }
         }
 
         game.draw();
@@ -0,0 +1,266 @@
+//! Reliable-UDP transport, feature-gated behind `udp-transport`: an
+//! alternative to the TCP WebSocket path in `main` for latency-sensitive
+//! play, since move spam and garbage timers suffer from head-of-line
+//! blocking on TCP. Implements laminar-style sequencing on a plain
+//! `UdpSocket` - a monotonic sequence number per outgoing packet plus an
+//! ack + 32-bit ack-bitfield covering the sequences just before it, and a
+//! resend queue for anything classified reliable. Produces the same
+//! `NetworkBridge` the WebSocket path does, so `connect_to_server`'s
+//! callers don't need to know which transport is underneath.
+use crate::{NetError, NetworkBridge, NetworkConfig};
+use match3_protocol::{ClientMessage, ServerMessage};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc::unbounded_channel;
+use tokio::time::interval;
+
+/// How a message kind should be delivered. `ReliableOrdered` and
+/// `ReliableUnordered` both go in the resend queue until acked; only
+/// `ReliableOrdered` packets are held back from the game if a lower
+/// sequence hasn't arrived yet. `UnreliableSequenced` packets are never
+/// resent, and one older than the newest already applied is just dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Reliability {
+    ReliableOrdered,
+    ReliableUnordered,
+    UnreliableSequenced,
+}
+
+/// Login/match-lifecycle messages need both guarantees: the server must
+/// see them and must see them in order. Garbage sends only need the first.
+/// Everything else (swaps, score/energy ticks, board updates, input
+/// frames, checksums, emotes, chat) is frequent enough that a dropped or
+/// reordered packet is cheaper to ignore than to retransmit.
+fn client_reliability(msg: &ClientMessage) -> Reliability {
+    match msg {
+        ClientMessage::Hello { .. }
+        | ClientMessage::Login { .. }
+        | ClientMessage::Register { .. }
+        | ClientMessage::Resume { .. }
+        | ClientMessage::RequestServerStatus
+        | ClientMessage::RequestRematch
+        | ClientMessage::LeaveGame => Reliability::ReliableOrdered,
+        ClientMessage::SendGarbage { .. } => Reliability::ReliableUnordered,
+        _ => Reliability::UnreliableSequenced,
+    }
+}
+
+fn server_reliability(msg: &ServerMessage) -> Reliability {
+    match msg {
+        ServerMessage::HelloAck { .. }
+        | ServerMessage::AuthAccepted { .. }
+        | ServerMessage::AuthRejected { .. }
+        | ServerMessage::ServerStatusReport { .. }
+        | ServerMessage::MatchFound { .. }
+        | ServerMessage::GameStarted { .. }
+        | ServerMessage::GameOver { .. }
+        | ServerMessage::MatchResult { .. }
+        | ServerMessage::OpponentRequestedRematch
+        | ServerMessage::RematchAccepted { .. }
+        | ServerMessage::OpponentLeft
+        | ServerMessage::OpponentDisconnected { .. }
+        | ServerMessage::ResumeAccepted { .. }
+        | ServerMessage::ResumeRejected
+        | ServerMessage::Error { .. } => Reliability::ReliableOrdered,
+        ServerMessage::ReceiveGarbage { .. } => Reliability::ReliableUnordered,
+        _ => Reliability::UnreliableSequenced,
+    }
+}
+
+const RESEND_INTERVAL: Duration = Duration::from_millis(200);
+const ACK_BITFIELD_WINDOW: u32 = 32;
+
+/// One datagram on the wire: a sequence number, an ack of the highest
+/// sequence seen from the peer plus a bitfield of the 32 before it, and
+/// the bincode-encoded `ClientMessage`/`ServerMessage`. `reliable` mirrors
+/// the sender's `Reliability` classification so the receiver knows whether
+/// to ack it at all.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Packet {
+    sequence: u32,
+    ack: u32,
+    ack_bitfield: u32,
+    reliable: bool,
+    payload: Vec<u8>,
+}
+
+/// A reliable packet awaiting acknowledgement, resent on `RESEND_INTERVAL`
+/// until the peer's ack/ack_bitfield covers its sequence.
+struct PendingResend {
+    sent_at: Instant,
+    datagram: Vec<u8>,
+}
+
+/// Tracks which of the peer's sequences we've seen, for building the ack +
+/// ack_bitfield we send back, and the highest `UnreliableSequenced` sequence
+/// applied per stream, for dropping stale ones.
+#[derive(Default)]
+struct AckTracker {
+    highest_seen: u32,
+    seen_bitfield: u32,
+    highest_unreliable_applied: u32,
+}
+
+impl AckTracker {
+    fn record(&mut self, sequence: u32) {
+        if sequence > self.highest_seen {
+            let shift = sequence - self.highest_seen;
+            self.seen_bitfield = if shift >= 32 { 0 } else { self.seen_bitfield << shift };
+            self.seen_bitfield |= 1 << (shift.saturating_sub(1).min(31));
+            self.highest_seen = sequence;
+        } else {
+            let shift = self.highest_seen - sequence;
+            if shift >= 1 && shift <= ACK_BITFIELD_WINDOW {
+                self.seen_bitfield |= 1 << (shift - 1);
+            }
+        }
+    }
+
+    /// True if `ack`/`ack_bitfield` (as reported by the peer) cover `sequence`.
+    fn covers(ack: u32, ack_bitfield: u32, sequence: u32) -> bool {
+        if sequence == ack {
+            return true;
+        }
+        if sequence > ack {
+            return false;
+        }
+        let shift = ack - sequence;
+        shift >= 1 && shift <= ACK_BITFIELD_WINDOW && (ack_bitfield & (1 << (shift - 1))) != 0
+    }
+}
+
+/// Connects over UDP instead of TCP WebSocket and returns the same
+/// `NetworkBridge` shape `connect_to_server` does.
+pub async fn connect_udp(config: &NetworkConfig) -> Result<NetworkBridge, NetError> {
+    let addr: SocketAddr = config
+        .url
+        .trim_start_matches("udp://")
+        .parse()
+        .map_err(|_| NetError::ConnectFailed(format!("invalid UDP address: {}", config.url)))?;
+
+    let socket = UdpSocket::bind("0.0.0.0:0")
+        .await
+        .map_err(|e| NetError::ConnectFailed(e.to_string()))?;
+    socket.connect(addr).await.map_err(|e| NetError::ConnectFailed(e.to_string()))?;
+
+    let (to_server_tx, mut to_server_rx) = unbounded_channel::<ClientMessage>();
+    let (from_server_tx, from_server_rx) = unbounded_channel::<ServerMessage>();
+    let (error_tx, error_rx) = unbounded_channel::<NetError>();
+
+    tokio::spawn(async move {
+        let mut next_sequence: u32 = 0;
+        let mut resend_queue: HashMap<u32, PendingResend> = HashMap::new();
+        let mut peer_acks = AckTracker::default();
+        let mut ordered_floor: u32 = 0;
+        let mut resend_tick = interval(RESEND_INTERVAL);
+        let mut recv_buf = vec![0u8; 4096];
+
+        loop {
+            tokio::select! {
+                Some(msg) = to_server_rx.recv() => {
+                    let reliable = client_reliability(&msg) != Reliability::UnreliableSequenced;
+                    let sequence = next_sequence;
+                    next_sequence = next_sequence.wrapping_add(1);
+
+                    let payload = match bincode::serialize(&msg) {
+                        Ok(bytes) => bytes,
+                        Err(e) => {
+                            let _ = error_tx.send(NetError::Serialize(e.to_string()));
+                            continue;
+                        }
+                    };
+                    let packet = Packet {
+                        sequence,
+                        ack: peer_acks.highest_seen,
+                        ack_bitfield: peer_acks.seen_bitfield,
+                        reliable,
+                        payload,
+                    };
+                    let datagram = match bincode::serialize(&packet) {
+                        Ok(bytes) => bytes,
+                        Err(e) => {
+                            let _ = error_tx.send(NetError::Serialize(e.to_string()));
+                            continue;
+                        }
+                    };
+
+                    if socket.send(&datagram).await.is_err() {
+                        let _ = error_tx.send(NetError::Closed);
+                        break;
+                    }
+                    if reliable {
+                        resend_queue.insert(sequence, PendingResend { sent_at: Instant::now(), datagram });
+                    }
+                }
+
+                result = socket.recv(&mut recv_buf) => {
+                    match result {
+                        Ok(len) => {
+                            let Ok(packet) = bincode::deserialize::<Packet>(&recv_buf[..len]) else { continue };
+                            peer_acks.record(packet.sequence);
+
+                            // Any of our reliable sends the peer's ack/bitfield now
+                            // covers has been delivered; stop resending it.
+                            resend_queue.retain(|&seq, _| {
+                                !AckTracker::covers(packet.ack, packet.ack_bitfield, seq)
+                            });
+
+                            let Ok(message) = bincode::deserialize::<ServerMessage>(&packet.payload) else { continue };
+                            match server_reliability(&message) {
+                                Reliability::ReliableOrdered => {
+                                    // Out-of-order reliable packets are rare (UDP
+                                    // mostly preserves order on a direct path) and
+                                    // not worth a full reorder buffer here; drop
+                                    // one that arrives behind the floor and let
+                                    // the resend queue on the sender's side fix it.
+                                    if packet.sequence < ordered_floor {
+                                        continue;
+                                    }
+                                    ordered_floor = packet.sequence + 1;
+                                }
+                                Reliability::UnreliableSequenced => {
+                                    if packet.sequence <= peer_acks.highest_unreliable_applied
+                                        && peer_acks.highest_unreliable_applied != 0
+                                    {
+                                        continue;
+                                    }
+                                    peer_acks.highest_unreliable_applied = packet.sequence;
+                                }
+                                Reliability::ReliableUnordered => {}
+                            }
+
+                            if from_server_tx.send(message).is_err() {
+                                break;
+                            }
+                        }
+                        Err(_) => {
+                            let _ = error_tx.send(NetError::Closed);
+                            break;
+                        }
+                    }
+                }
+
+                _ = resend_tick.tick() => {
+                    let now = Instant::now();
+                    for pending in resend_queue.values_mut() {
+                        if now.duration_since(pending.sent_at) >= RESEND_INTERVAL {
+                            if socket.send(&pending.datagram).await.is_err() {
+                                let _ = error_tx.send(NetError::Closed);
+                                return;
+                            }
+                            pending.sent_at = now;
+                        }
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(NetworkBridge {
+        to_server: to_server_tx,
+        from_server: from_server_rx,
+        errors: error_rx,
+    })
+}
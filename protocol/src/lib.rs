@@ -4,41 +4,201 @@ use uuid::Uuid;
 pub type PlayerId = Uuid;
 pub type GameId = Uuid;
 
+/// Bump whenever `ClientMessage`/`ServerMessage` gain, remove, or change the
+/// shape of a variant. The server compares this against `MIN_SUPPORTED_PROTOCOL_VERSION`
+/// to reject clients it can no longer safely speak to (see `Hello`/`HelloAck`).
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Oldest client protocol version this server still accepts. Raise this
+/// (never lower it) when a breaking change makes older clients unsafe to
+/// keep matchmaking, e.g. a changed gem-type byte encoding or booster id.
+pub const MIN_SUPPORTED_PROTOCOL_VERSION: u32 = 1;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum ClientMessage {
-    Login { username: String },
+    /// Mandatory first message on every connection, before `Login` or
+    /// `Resume`. The server answers with `HelloAck` before processing
+    /// anything else, so mismatched clients never reach matchmaking.
+    Hello { protocol_version: u32, client_build: String },
+    /// Asks for a `ServerStatusReport` in place of `Login`/`Resume`, then
+    /// disconnects without ever joining. Lets the server-browser screen show
+    /// live player/queue counts before the player commits to a server.
+    RequestServerStatus,
+    Login { username: String, password: String },
+    /// Creates a brand-new account; rejected with `AuthRejected` if
+    /// `username` is already taken. Unlike `Login`, never implicitly creates
+    /// an account, so a typo'd username can't silently steal an empty slot.
+    Register { username: String, password: String },
     JoinQueue,
     SwapGems { row1: usize, col1: usize, row2: usize, col2: usize },
     ScoreUpdate { score: u32 },
     SendGarbage { amount: u8 },
     ActivateSpecial { row: usize, col: usize },
     ActivateBooster { booster_id: u8 },
+    SendEmote { emote_id: u8 },
     RequestRematch,
     LeaveGame,
+    /// Sent instead of `LeaveGame` when the client's own AFK timer (not the
+    /// player) ends the match, so the server can record it as a forfeit loss
+    /// rather than a plain disconnect.
+    Forfeit,
+    /// Re-identifies an already-authenticated player after a dropped
+    /// WebSocket, so the server can reattach them to their in-progress game
+    /// instead of treating the new connection as a fresh login.
+    /// `known_board_version` is the opponent board version the client last
+    /// applied (0 if it never received one this match), so the server can
+    /// skip re-sending the grid when nothing changed while it was gone.
+    Resume { session_token: String, known_board_version: u64 },
+    /// Sent periodically by a playing client so the server can relay its
+    /// board to the opponent's `opponent_grid` and to any spectators, since
+    /// the server itself never simulates the match-3 grid.
+    BoardUpdate { grid: Vec<u8>, score: u32, energy: u32 },
+    /// Registers the caller as a spectator of an in-progress game; they
+    /// start receiving `BoardSnapshot` broadcasts for it.
+    SpectateGame { game_id: GameId },
+    /// Asks for the set of currently-active matches, so a client browsing
+    /// the Leaderboard screen can pick one to `SpectateGame`. Answered with
+    /// `MatchList`.
+    ListMatches,
+    /// Requests the leaderboard. `since_version` is the version the client
+    /// already has cached; the server replies `LeaderboardUnchanged` if its
+    /// version hasn't advanced, sparing a re-sort and re-transmit of the
+    /// full table on every open.
+    FetchLeaderboard { since_version: u64 },
+    /// Requests a page of the caller's own completed-match history, newest
+    /// first. `before` is an opaque cursor - echo back a previous reply's
+    /// `next_cursor` to fetch the next page, or pass `None` for the most
+    /// recent `limit` matches.
+    RequestHistory { limit: u32, before: Option<i64> },
+    /// Requests the chronological set list and overall record between the
+    /// caller and `opponent_id`, for a rivalry-stats view before a rematch.
+    /// Answered with `HeadToHead`.
+    RequestHeadToHead { opponent_id: PlayerId },
+    /// An in-match chat line. The server applies per-sender flood
+    /// protection before relaying it as `ServerMessage::Chat`; a flooded
+    /// sender gets back an `Error` instead, and nothing is broadcast.
+    Chat { text: String },
+    /// One lockstep input packet: `inputs` is a `bincode`-serialized
+    /// `Vec<PlayerInput>` (see the client's `LockstepState`) destined for
+    /// simulation frame `frame`. The server never inspects the payload, only
+    /// relays it to the opponent as `OpponentInputFrame`.
+    InputFrame { frame: u32, inputs: Vec<u8> },
+    /// A compact fingerprint of the sender's board state at `frame` (gem
+    /// grid, garbage queue, score), sent every few frames so the opponent
+    /// can catch the two boards drifting apart. The server never computes
+    /// or checks `hash` itself, only relays it as `OpponentStateChecksum`.
+    StateChecksum { frame: u32, hash: u64 },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum ServerMessage {
-    AuthAccepted { player_id: PlayerId, username: String, elo: i32, wins: u32, losses: u32 },
+    /// Answers `Hello`. `accepted` is false if the client's `protocol_version`
+    /// is below `min_supported`; the client should show an "update required"
+    /// message and not attempt to log in.
+    HelloAck { accepted: bool, server_version: u32, min_supported: u32 },
+    /// `rating`/`rating_deviation` are the Glicko-2 `r`/`RD` pair - `rating`
+    /// alone is the familiar ~1500-centered number, `rating_deviation` is how
+    /// confident the server still is in it (see `MatchResult`).
+    AuthAccepted { player_id: PlayerId, username: String, rating: f64, rating_deviation: f64, wins: u32, losses: u32, session_token: String },
+    /// Answers `RequestServerStatus`: how many players are connected and how
+    /// many are waiting in the matchmaking queue right now. The connection
+    /// is closed immediately after this is sent.
+    ServerStatusReport { players_online: usize, queue_size: usize },
     AuthRejected { reason: String },
     Connected { player_id: PlayerId },
     Queued { position: usize },
-    MatchFound { game_id: GameId, opponent_id: PlayerId },
-    GameStarted { game_id: GameId },
+    /// `win_probability` is the matchmaker's pre-match estimate of the
+    /// recipient's own chance to win, from the plain Glicko expected-score
+    /// formula `E = 1/(1+10^((opp−me)/400))` (see `confidence_intervals_overlap`
+    /// for how the pairing itself was chosen).
+    MatchFound { game_id: GameId, opponent_id: PlayerId, opponent_name: String, win_probability: f64 },
+    /// `seed` is the shared board RNG seed: both clients seed a `StdRng` from
+    /// it and consume it in the same fixed order — including the same
+    /// reject-and-redraw rule for any initial three-in-a-row — so their
+    /// boards (and a server-side replay) stay byte-identical without
+    /// trusting client state.
+    GameStarted { game_id: GameId, seed: u64 },
     OpponentSwap { row1: usize, col1: usize, row2: usize, col2: usize },
     ScoreUpdate { player_score: u32, opponent_score: u32 },
     TimeUpdate { seconds_remaining: u64 },
     ReceiveGarbage { amount: u8 },
     OpponentActivatedSpecial { row: usize, col: usize },
     OpponentActivatedBooster { booster_id: u8 },
+    OpponentEmote { emote_id: u8 },
     GameOver { winner: GameResult },
-    MatchResult { new_elo: i32, elo_change: i32, wins: u32, losses: u32 },
+    /// `new_rating`/`new_rd` are the Glicko-2 `r`/`RD` pair after this match's
+    /// update; `rating_change` is `new_rating` minus the pre-match rating, so
+    /// the client can show a signed delta the way it used to for flat ELO.
+    MatchResult { new_rating: f64, rating_change: f64, new_rd: f64, wins: u32, losses: u32 },
     OpponentRequestedRematch,
-    RematchAccepted,
+    /// Carries a freshly-rolled board seed, same purpose as `GameStarted`'s,
+    /// so the rematch doesn't replay the prior match's grid.
+    RematchAccepted { seed: u64 },
     OpponentLeft,
-    OpponentDisconnected,
+    /// The opponent's transport dropped, but the match isn't over: the
+    /// server pauses its countdown and gives them `grace_seconds` to
+    /// reconnect (see `Resume`) before finalizing the match as a forfeit.
+    OpponentDisconnected { grace_seconds: u64 },
+    /// The opponent that previously triggered `OpponentDisconnected`
+    /// reconnected within the grace period; the countdown has resumed.
+    OpponentReconnected,
+    /// Rehydrates a resumed session: the rejoining client restores its board
+    /// and timers from these values instead of reinitializing. `board_version`
+    /// is the session's current opponent-board version; a `BoardSnapshot` or
+    /// `BoardUnchanged` follows depending on whether it matches the client's
+    /// `Resume.known_board_version`.
+    ResumeAccepted {
+        game_id: GameId,
+        seconds_remaining: u64,
+        player_score: u32,
+        opponent_score: u32,
+        pending_garbage: u8,
+        board_version: u64,
+    },
+    ResumeRejected,
+    /// A board state pushed to an opponent or spectator. `grid` is row-major,
+    /// one byte per cell encoding the gem type (see `GemType`'s wire ids on
+    /// the client); `score` and `energy` belong to whichever player the
+    /// board came from. `version` increases monotonically per session, one
+    /// tick per `BoardUpdate` relayed, so a resuming client can tell whether
+    /// its cached copy is already current.
+    BoardSnapshot { grid: Vec<u8>, score: u32, energy: u32, version: u64 },
+    /// Answers `Resume` in place of `BoardSnapshot` when the client's
+    /// `known_board_version` already matches the session's current one,
+    /// sparing a grid re-transmit and re-decode on every reconnect.
+    BoardUnchanged,
+    /// Answers `FetchLeaderboard` when the requested `since_version` is
+    /// stale: the full, freshly-sorted table plus the version it reflects.
+    /// Each entry's `f64` is the player's Glicko-2 rating.
+    LeaderboardData { version: u64, players: Vec<(String, f64)> },
+    /// Answers `FetchLeaderboard` when the caller's `since_version` already
+    /// matches the server's current version; the client should keep its
+    /// cached `leaderboard_data` as-is.
+    LeaderboardUnchanged,
+    /// Answers `ListMatches`: one `(game_id, player1_name, player2_name)`
+    /// entry per currently-active match, for the Leaderboard screen's
+    /// spectate list.
+    MatchList { matches: Vec<(GameId, String, String)> },
+    /// Answers `RequestHistory`: up to `limit` of the caller's own completed
+    /// matches, newest first. `next_cursor` is `Some` (pass it back as the
+    /// next `RequestHistory.before`) only if a full page was returned, since
+    /// a short page means there's nothing older left.
+    MatchHistory { matches: Vec<MatchRecord>, next_cursor: Option<i64> },
+    /// Answers `RequestHeadToHead`: up to a fixed number of the caller's most
+    /// recent matches against that one opponent, plus the caller's overall
+    /// win/loss record against them across their entire history.
+    HeadToHead { matches: Vec<MatchRecord>, wins: u32, losses: u32 },
+    /// Relays an opponent's `InputFrame` verbatim; `inputs` decodes the same
+    /// way on the receiving end.
+    OpponentInputFrame { frame: u32, inputs: Vec<u8> },
+    /// Relays an opponent's `StateChecksum` verbatim, for comparison against
+    /// the receiver's own recorded hash for that `frame`.
+    OpponentStateChecksum { frame: u32, hash: u64 },
+    /// A chat line accepted by flood protection, broadcast to both players
+    /// in the match (including the sender, as an echo/confirmation).
+    Chat { from: String, text: String },
     Error { message: String },
 }
 
@@ -48,3 +208,18 @@ pub enum GameResult {
     Loss,
     Tie,
 }
+
+/// One row of a `MatchHistory` reply, from the requesting player's point of
+/// view - `result`/`player_score`/`rating_change` are all relative to them, not
+/// a fixed player1/player2 ordering. `match_id` doubles as the pagination
+/// cursor for the next `RequestHistory.before`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchRecord {
+    pub match_id: i64,
+    pub opponent_name: String,
+    pub player_score: u32,
+    pub opponent_score: u32,
+    pub result: GameResult,
+    pub rating_change: f64,
+    pub played_at: i64,
+}
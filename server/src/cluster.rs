@@ -0,0 +1,387 @@
+//! Multi-node clustering: lets several server processes, each holding its own
+//! set of WebSocket connections, referee a match whose two players are
+//! connected to different nodes. A node "owns" a `GameId` if it runs that
+//! match's `GameSession` actor; ownership is a pure function of the id
+//! (`ClusterConfig::owning_node`) plus the static peer list, so every node
+//! can check it without a coordination round-trip. The node that *doesn't*
+//! own a given match represents its locally-connected player to the owner's
+//! actor with a proxy `Player` whose `tx` re-serializes outgoing messages
+//! over HTTP instead of a local socket (see `ServerState::create_cross_node_match`
+//! in `main.rs`) - the actor code itself never needs to know a player is
+//! remote.
+use crate::{db, Player, ServerState, Tx};
+use match3_protocol::{ClientMessage, PlayerId};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::RwLock;
+use tokio_tungstenite::tungstenite::Message;
+use uuid::Uuid;
+
+pub type NodeId = String;
+
+/// Header carrying the shared secret that authenticates cluster-internal
+/// traffic - see `ClusterConfig::shared_secret`.
+const CLUSTER_SECRET_HEADER: &str = "x-cluster-secret";
+
+/// Static cluster membership, loaded once at startup - read-only for the
+/// life of the process, the same way `GAME_DURATION` and friends are fixed
+/// constants rather than hot-reloaded config.
+#[derive(Debug, Clone)]
+pub struct ClusterConfig {
+    pub local_node_id: NodeId,
+    /// This node's own address, handed to peers so they know where to relay
+    /// messages and forwarded commands back to.
+    pub local_addr: String,
+    /// Peer node id -> base URL (e.g. "http://10.0.0.2:9001").
+    pub peers: HashMap<NodeId, String>,
+    /// Shared secret every peer must present (via `CLUSTER_SECRET_HEADER`)
+    /// before `handle_cluster_connection` will dispatch their request -
+    /// `/cluster/*` otherwise trusts whatever can reach its bind address.
+    /// `None` (the env var unset) leaves the listener open, the same
+    /// permissive default as an empty `CLUSTER_PEERS`.
+    pub shared_secret: Option<String>,
+}
+
+impl ClusterConfig {
+    /// Reads `CLUSTER_NODE_ID` (default "local"), `CLUSTER_LOCAL_ADDR`
+    /// (default empty - fine as long as there are no peers to call back),
+    /// `CLUSTER_PEERS`, a comma-separated `node_id=http://host:port` list
+    /// (default empty, i.e. single-node / no clustering), and
+    /// `CLUSTER_SHARED_SECRET` (default unset, i.e. `/cluster/*` open to
+    /// anything that can reach the bind address).
+    pub fn load() -> Self {
+        let local_node_id = std::env::var("CLUSTER_NODE_ID").unwrap_or_else(|_| "local".to_string());
+        let local_addr = std::env::var("CLUSTER_LOCAL_ADDR").unwrap_or_default();
+        let mut peers = HashMap::new();
+        if let Ok(raw) = std::env::var("CLUSTER_PEERS") {
+            for entry in raw.split(',').filter(|s| !s.is_empty()) {
+                if let Some((id, addr)) = entry.split_once('=') {
+                    peers.insert(id.to_string(), addr.to_string());
+                }
+            }
+        }
+        let shared_secret = std::env::var("CLUSTER_SHARED_SECRET").ok().filter(|s| !s.is_empty());
+        ClusterConfig { local_node_id, local_addr, peers, shared_secret }
+    }
+
+    /// Every node id in the cluster (this one plus every peer), in a fixed
+    /// order so `owning_node` gives the same answer everywhere.
+    fn all_node_ids(&self) -> Vec<&NodeId> {
+        let mut ids: Vec<&NodeId> = std::iter::once(&self.local_node_id).chain(self.peers.keys()).collect();
+        ids.sort();
+        ids
+    }
+
+    /// Deterministically picks which node owns `game_id`'s `GameSession`
+    /// actor. A freshly-created match picks a `game_id` by rejection
+    /// sampling fresh UUIDs until one of them is locally owned, so match
+    /// creation never needs to ask a peer who should run it.
+    pub fn owning_node(&self, game_id: Uuid) -> NodeId {
+        let ids = self.all_node_ids();
+        let index = (game_id.as_u128() % ids.len() as u128) as usize;
+        ids[index].clone()
+    }
+
+    pub fn is_local(&self, node_id: &str) -> bool {
+        node_id == self.local_node_id
+    }
+
+    /// Peers in a fixed order, for round-robin cross-node matchmaking polls.
+    pub fn peer_addrs(&self) -> Vec<String> {
+        let mut ids: Vec<&NodeId> = self.peers.keys().collect();
+        ids.sort();
+        ids.into_iter().filter_map(|id| self.peers.get(id).cloned()).collect()
+    }
+}
+
+#[derive(Debug)]
+pub enum ClusterError {
+    Http(reqwest::Error),
+}
+
+impl From<reqwest::Error> for ClusterError {
+    fn from(e: reqwest::Error) -> Self {
+        ClusterError::Http(e)
+    }
+}
+
+impl std::fmt::Display for ClusterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClusterError::Http(e) => write!(f, "cluster HTTP error: {}", e),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct RelayEnvelope {
+    player_id: PlayerId,
+    message_json: String,
+}
+
+/// The asking node's own queued player's rating, so the answering node can
+/// run the same `db::confidence_intervals_overlap` check `find_compatible_pair`
+/// already applies to local pairs - see `handle_cluster_connection`'s
+/// `/cluster/match_request` arm.
+#[derive(Serialize, Deserialize)]
+struct MatchRequestQuery {
+    rating: f64,
+    rating_deviation: f64,
+    waited_secs: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MatchRequestResponse {
+    player_id: PlayerId,
+    username: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CommandEnvelope {
+    player_id: PlayerId,
+    message: ClientMessage,
+}
+
+/// Thin HTTP client for talking to peer nodes. Cheap to clone - `reqwest::Client`
+/// is itself a handle around a pooled connection manager.
+#[derive(Clone)]
+pub struct ClusterClient {
+    http: reqwest::Client,
+    /// Same value as `ClusterConfig::shared_secret`, attached to every
+    /// outgoing `/cluster/*` request via `CLUSTER_SECRET_HEADER` - loaded
+    /// independently here rather than threaded in from `ClusterConfig`,
+    /// the same way this client and that config already read their own
+    /// slice of the cluster env vars separately.
+    shared_secret: Option<String>,
+}
+
+impl ClusterClient {
+    pub fn new() -> Self {
+        ClusterClient {
+            http: reqwest::Client::new(),
+            shared_secret: std::env::var("CLUSTER_SHARED_SECRET").ok().filter(|s| !s.is_empty()),
+        }
+    }
+
+    /// Forwards an already-serialized `ServerMessage` (the same JSON string
+    /// a local `player.tx.send` would carry) to `player_id`'s real
+    /// connection on the node at `peer_addr`. Best-effort: a failed relay
+    /// just drops the update, the same way a lagging local `Tx::send` is
+    /// already ignored throughout this server.
+    pub async fn relay(&self, peer_addr: &str, player_id: PlayerId, message_json: String) -> Result<(), ClusterError> {
+        self.with_secret(self.http.post(format!("{}/cluster/relay", peer_addr)))
+            .json(&RelayEnvelope { player_id, message_json })
+            .send()
+            .await?;
+        Ok(())
+    }
+
+    /// Asks `peer_addr` for one of its own locally-queued players whose
+    /// rating is close enough to `rating`/`rating_deviation` (per the same
+    /// confidence-interval check `find_compatible_pair` uses locally) to be
+    /// worth a cross-node match. `waited_secs` is how long the asking
+    /// player has been queued, widening the acceptable gap the same way it
+    /// would for a local pair. `None` if the peer has nobody compatible
+    /// waiting.
+    pub async fn request_match(&self, peer_addr: &str, rating: f64, rating_deviation: f64, waited_secs: f64) -> Result<Option<(PlayerId, String)>, ClusterError> {
+        let resp = self
+            .with_secret(self.http.post(format!("{}/cluster/match_request", peer_addr)))
+            .json(&MatchRequestQuery { rating, rating_deviation, waited_secs })
+            .send()
+            .await?;
+        if resp.status() == reqwest::StatusCode::NO_CONTENT {
+            return Ok(None);
+        }
+        let body: MatchRequestResponse = resp.json().await?;
+        Ok(Some((body.player_id, body.username)))
+    }
+
+    /// Forwards a game-scoped `ClientMessage` from a locally-connected
+    /// player to the node that actually owns their match's actor.
+    pub async fn forward_command(&self, peer_addr: &str, player_id: PlayerId, message: ClientMessage) -> Result<(), ClusterError> {
+        self.with_secret(self.http.post(format!("{}/cluster/command", peer_addr)))
+            .json(&CommandEnvelope { player_id, message })
+            .send()
+            .await?;
+        Ok(())
+    }
+
+    /// Attaches `CLUSTER_SECRET_HEADER` to a request builder when this node
+    /// has a shared secret configured.
+    fn with_secret(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.shared_secret {
+            Some(secret) => builder.header(CLUSTER_SECRET_HEADER, secret),
+            None => builder,
+        }
+    }
+}
+
+/// Minimal hand-rolled HTTP/1.1 server for the three `/cluster/*` endpoints.
+/// Nothing else in this server pulls in a web framework (see the equally
+/// hand-rolled packet framing in `udp_transport.rs` on the client side), so
+/// this follows the same house style rather than adding one for three
+/// internal, peer-only routes. Gated on `ClusterConfig::shared_secret` when
+/// one is configured - see `handle_cluster_connection`.
+pub async fn run_cluster_listener(bind_addr: SocketAddr, state: ServerState) {
+    let listener = match TcpListener::bind(bind_addr).await {
+        Ok(l) => l,
+        Err(e) => {
+            println!("Cluster listener failed to bind {}: {}", bind_addr, e);
+            return;
+        }
+    };
+    println!("Cluster listener on {}", bind_addr);
+
+    loop {
+        let (stream, _) = match listener.accept().await {
+            Ok(pair) => pair,
+            Err(_) => continue,
+        };
+        let state = state.clone();
+        tokio::spawn(handle_cluster_connection(stream, state));
+    }
+}
+
+async fn handle_cluster_connection(mut stream: TcpStream, state: ServerState) {
+    let Some((path, headers, body)) = read_http_request(&mut stream).await else { return };
+
+    if let Some(expected) = &state.cluster.shared_secret {
+        if headers.get(CLUSTER_SECRET_HEADER) != Some(expected) {
+            let _ = stream.write_all(b"HTTP/1.1 401 Unauthorized\r\nContent-Length: 0\r\n\r\n").await;
+            return;
+        }
+    }
+
+    let response_body = match path.as_str() {
+        "/cluster/relay" => {
+            if let Ok(envelope) = serde_json::from_slice::<RelayEnvelope>(&body) {
+                deliver_local(&state.players, envelope.player_id, envelope.message_json).await;
+            }
+            None
+        }
+        "/cluster/match_request" => {
+            match serde_json::from_slice::<MatchRequestQuery>(&body) {
+                Ok(query) => match find_cross_node_candidate(&state, &query).await {
+                    Some((player_id, username)) => serde_json::to_vec(&MatchRequestResponse { player_id, username }).ok(),
+                    None => None,
+                },
+                Err(_) => None,
+            }
+        }
+        "/cluster/command" => {
+            if let Ok(envelope) = serde_json::from_slice::<CommandEnvelope>(&body) {
+                state.handle_client_message(envelope.player_id, envelope.message).await;
+            }
+            None
+        }
+        _ => None,
+    };
+
+    let status = if response_body.is_some() { "200 OK" } else { "204 No Content" };
+    let body_bytes = response_body.unwrap_or_default();
+    let header = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+        status,
+        body_bytes.len()
+    );
+    let _ = stream.write_all(header.as_bytes()).await;
+    let _ = stream.write_all(&body_bytes).await;
+}
+
+/// Scans the local matchmaking queue for the first player whose rating is
+/// close enough to the cross-node asker's (`query`) per
+/// `db::confidence_intervals_overlap` - the same check `find_compatible_pair`
+/// already applies to local pairs - removing and returning them if found.
+/// Re-checks the candidate is still queued under the lock that removes
+/// them, the same way `find_compatible_pair` guards against a concurrent
+/// local match claiming the same player first.
+async fn find_cross_node_candidate(state: &ServerState, query: &MatchRequestQuery) -> Option<(PlayerId, String)> {
+    let now = Instant::now();
+    let snapshot = state.matchmaking_queue.lock().await.clone();
+
+    for (id, joined_at) in snapshot {
+        let Ok(user) = state.db.get_user_by_id(id).await else { continue };
+        let waited_secs = query.waited_secs.max(now.duration_since(joined_at).as_secs_f64());
+
+        if db::confidence_intervals_overlap(query.rating, query.rating_deviation, user.rating, user.rating_deviation, waited_secs) {
+            let mut queue = state.matchmaking_queue.lock().await;
+            if !queue.iter().any(|(qid, _)| *qid == id) {
+                continue;
+            }
+            queue.retain(|(qid, _)| *qid != id);
+            state.metrics.queue_length.set(queue.len() as i64);
+            let username = state.usernames.read().await.get(&id).cloned().unwrap_or_default();
+            return Some((id, username));
+        }
+    }
+
+    None
+}
+
+async fn deliver_local(players: &Arc<RwLock<HashMap<PlayerId, Player>>>, player_id: PlayerId, message_json: String) {
+    if let Some(player) = players.read().await.get(&player_id) {
+        let _ = player.tx.read().await.send(Message::Text(message_json));
+    }
+}
+
+/// Reads one HTTP/1.1 request off `stream` and returns its path, headers
+/// (lower-cased names), and body. Good enough for trusted same-cluster
+/// traffic between nodes that both speak this exact protocol - not a
+/// general-purpose HTTP parser.
+async fn read_http_request(stream: &mut TcpStream) -> Option<(String, HashMap<String, String>, Vec<u8>)> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    loop {
+        let n = stream.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+
+        let Some(header_end) = buf.windows(4).position(|w| w == b"\r\n\r\n") else { continue };
+        let headers_text = std::str::from_utf8(&buf[..header_end]).ok()?;
+        let mut lines = headers_text.lines();
+        let request_line = lines.next()?;
+        let path = request_line.split_whitespace().nth(1)?.to_string();
+
+        let headers: HashMap<String, String> = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+            .collect();
+
+        let content_length: usize = headers.get("content-length").and_then(|v| v.parse().ok()).unwrap_or(0);
+
+        let body_start = header_end + 4;
+        while buf.len() < body_start + content_length {
+            let n = stream.read(&mut chunk).await.ok()?;
+            if n == 0 {
+                break;
+            }
+            buf.extend_from_slice(&chunk[..n]);
+        }
+
+        let body = buf[body_start..(body_start + content_length).min(buf.len())].to_vec();
+        return Some((path, headers, body));
+    }
+}
+
+/// Builds a proxy `Player` standing in for a real player connected to
+/// another node: anything sent to its `tx` is re-serialized as JSON and
+/// relayed to `peer_addr` over HTTP instead of a local socket.
+pub fn spawn_remote_player_proxy(cluster_client: ClusterClient, peer_addr: String, player_id: PlayerId) -> Player {
+    let (tx, mut rx): (Tx, _) = tokio::sync::mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        while let Some(msg) = rx.recv().await {
+            if let Message::Text(json) = msg {
+                let _ = cluster_client.relay(&peer_addr, player_id, json).await;
+            }
+        }
+    });
+    Player { id: player_id, tx: Arc::new(RwLock::new(tx)) }
+}
@@ -1,20 +1,118 @@
 use sqlx::{SqlitePool, Row};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
 use uuid::Uuid;
 
+/// How long a cached `User` is served from memory before the next read
+/// falls through to SQLite again - short enough that a rating change
+/// written by a concurrent match isn't visible stale for long, long enough
+/// to absorb the repeated by-id reads `update_match_result` and login do in
+/// quick succession.
+const USER_CACHE_TTL: Duration = Duration::from_secs(5);
+
+struct CacheEntry<V> {
+    value: V,
+    cached_at: Instant,
+}
+
+/// A small time-to-live cache, modeled on the `Arc<RwLock<HashMap<...>>>`
+/// state every other piece of shared server state already uses (see
+/// `ServerState` in `main.rs`) rather than pulling in a caching crate for
+/// one table. Cloning shares the same backing map, the same way `Database`
+/// shares its `SqlitePool` across clones.
+#[derive(Clone)]
+struct TtlCache<K, V> {
+    entries: Arc<RwLock<HashMap<K, CacheEntry<V>>>>,
+    ttl: Duration,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> TtlCache<K, V> {
+    fn new(ttl: Duration) -> Self {
+        Self { entries: Arc::new(RwLock::new(HashMap::new())), ttl }
+    }
+
+    async fn get(&self, key: &K) -> Option<V> {
+        let entries = self.entries.read().await;
+        entries
+            .get(key)
+            .filter(|entry| entry.cached_at.elapsed() < self.ttl)
+            .map(|entry| entry.value.clone())
+    }
+
+    async fn insert(&self, key: K, value: V) {
+        self.entries.write().await.insert(key, CacheEntry { value, cached_at: Instant::now() });
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct User {
     pub id: Uuid,
     pub username: String,
-    pub elo: i32,
+    /// Glicko-2 rating `r`, on the familiar ~1500-centered scale (not the
+    /// internal `μ` scale `glicko2_update` works in).
+    pub rating: f64,
+    /// Glicko-2 rating deviation `RD` - how uncertain `rating` still is.
+    /// Starts wide (350) for a new player and narrows as they play.
+    pub rating_deviation: f64,
+    /// Glicko-2 volatility `σ` - how erratically `rating` has been swinging.
+    /// Not shown to clients; only feeds the next `glicko2_update` call.
+    pub volatility: f64,
+    /// Unix timestamp of this player's last login or match, used by
+    /// `apply_inactivity_decay` to re-inflate `rating_deviation` for a
+    /// returning player who's been away for one or more rating periods.
+    pub last_played: i64,
     pub wins: u32,
     pub losses: u32,
     pub bricks: u32,
     pub gold: u32,
 }
 
+/// One stored row from `match_history`, from neither player's particular
+/// perspective; `get_match_history`'s caller reorders fields relative to
+/// whichever player they're building a `MatchRecord` for.
+#[derive(Debug, Clone)]
+pub struct MatchHistoryEntry {
+    pub id: i64,
+    pub player1_id: Uuid,
+    pub player2_id: Uuid,
+    pub player1_score: u32,
+    pub player2_score: u32,
+    pub player1_rating_change: f64,
+    pub player2_rating_change: f64,
+    pub winner_id: Option<Uuid>,
+    pub played_at: i64,
+}
+
+/// A `User` plus its Argon2id password hash, returned only from
+/// `get_user_by_username` so the hash never leaks into code paths (match
+/// results, leaderboard, etc.) that only need the public profile.
+pub struct UserCredentials {
+    pub user: User,
+    pub password_hash: String,
+}
+
+/// Errors `create_user` can return; kept distinct from a bare `sqlx::Error`
+/// so the caller can tell a duplicate username (expected, user-facing) apart
+/// from a genuine database failure.
+#[derive(Debug)]
+pub enum AuthDbError {
+    UsernameTaken,
+    Sqlx(sqlx::Error),
+}
+
+impl From<sqlx::Error> for AuthDbError {
+    fn from(e: sqlx::Error) -> Self {
+        AuthDbError::Sqlx(e)
+    }
+}
+
 #[derive(Clone)]
 pub struct Database {
     pool: SqlitePool,
+    user_cache: TtlCache<Uuid, User>,
 }
 
 impl Database {
@@ -29,11 +127,15 @@ impl Database {
             CREATE TABLE IF NOT EXISTS users (
                 id TEXT PRIMARY KEY,
                 username TEXT UNIQUE NOT NULL,
-                elo INTEGER DEFAULT 1000,
+                rating REAL DEFAULT 1500,
+                rating_deviation REAL DEFAULT 350,
+                volatility REAL DEFAULT 0.06,
+                last_played INTEGER DEFAULT (strftime('%s', 'now')),
                 wins INTEGER DEFAULT 0,
                 losses INTEGER DEFAULT 0,
                 bricks INTEGER DEFAULT 0,
                 gold INTEGER DEFAULT 0,
+                password_hash TEXT NOT NULL DEFAULT '',
                 created_at DATETIME DEFAULT CURRENT_TIMESTAMP
             )
             "#,
@@ -41,79 +143,155 @@ impl Database {
         .execute(&pool)
         .await?;
 
+        // Run migrations - create config table: small key/value store for
+        // server-wide tunables that aren't worth a dedicated column anywhere,
+        // starting with how long a "rating period" is for RD decay purposes
+        // (see `apply_inactivity_decay`).
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS config (
+                key TEXT PRIMARY KEY,
+                value REAL NOT NULL
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+        sqlx::query("INSERT OR IGNORE INTO config (key, value) VALUES ('decay_const_days', ?)")
+            .bind(DEFAULT_DECAY_CONST_DAYS)
+            .execute(&pool)
+            .await?;
+
+        // Run migrations - create match_history table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS match_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                player1_id TEXT NOT NULL,
+                player2_id TEXT NOT NULL,
+                player1_score INTEGER NOT NULL,
+                player2_score INTEGER NOT NULL,
+                player1_rating_change REAL NOT NULL,
+                player2_rating_change REAL NOT NULL,
+                winner_id TEXT,
+                played_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+
         println!("Database initialized successfully");
-        Ok(Database { pool })
+        Ok(Database { pool, user_cache: TtlCache::new(USER_CACHE_TTL) })
     }
 
-    /// Get user by username, or create if doesn't exist
-    pub async fn get_or_create_user(&self, username: &str) -> Result<User, sqlx::Error> {
-        // Try to get existing user first
-        let existing = sqlx::query(
-            "SELECT id, username, elo, wins, losses, bricks, gold FROM users WHERE username = ?"
+    /// Look up a user by username along with their password hash, for the
+    /// `Login` auth path to verify against. `None` if no such user exists.
+    pub async fn get_user_by_username(&self, username: &str) -> Result<Option<UserCredentials>, sqlx::Error> {
+        let row = sqlx::query(
+            "SELECT id, username, rating, rating_deviation, volatility, last_played, wins, losses, bricks, gold, password_hash FROM users WHERE username = ?"
         )
         .bind(username)
         .fetch_optional(&self.pool)
         .await?;
 
-        if let Some(row) = existing {
-            let id_str: String = row.get("id");
-            let id = Uuid::parse_str(&id_str).unwrap();
+        let Some(row) = row else { return Ok(None) };
+        let id_str: String = row.get("id");
+        let id = Uuid::parse_str(&id_str).unwrap();
 
-            return Ok(User {
+        Ok(Some(UserCredentials {
+            user: User {
                 id,
                 username: row.get("username"),
-                elo: row.get("elo"),
+                rating: row.get("rating"),
+                rating_deviation: row.get("rating_deviation"),
+                volatility: row.get("volatility"),
+                last_played: row.get("last_played"),
                 wins: row.get("wins"),
                 losses: row.get("losses"),
                 bricks: row.get("bricks"),
                 gold: row.get("gold"),
-            });
+            },
+            password_hash: row.get("password_hash"),
+        }))
+    }
+
+    /// Creates a brand-new account with the given pre-hashed password.
+    /// Fails with `AuthDbError::UsernameTaken` if the username is already in
+    /// use, rather than silently reusing the existing account the way
+    /// `get_or_create_user` used to.
+    pub async fn create_user(&self, username: &str, password_hash: &str) -> Result<User, AuthDbError> {
+        if self.get_user_by_username(username).await?.is_some() {
+            return Err(AuthDbError::UsernameTaken);
         }
 
-        // User doesn't exist - create new one
         let new_id = Uuid::new_v4();
         let id_str = new_id.to_string();
 
+        let last_played = now_unix();
         sqlx::query(
-            "INSERT INTO users (id, username, elo, wins, losses, bricks, gold) VALUES (?, ?, 1000, 0, 0, 0, 0)"
+            "INSERT INTO users (id, username, rating, rating_deviation, volatility, last_played, wins, losses, bricks, gold, password_hash)
+             VALUES (?, ?, ?, ?, ?, ?, 0, 0, 0, 0, ?)"
         )
         .bind(&id_str)
         .bind(username)
+        .bind(DEFAULT_RATING)
+        .bind(DEFAULT_RATING_DEVIATION)
+        .bind(DEFAULT_VOLATILITY)
+        .bind(last_played)
+        .bind(password_hash)
         .execute(&self.pool)
         .await?;
 
         println!("Created new user: {} ({})", username, new_id);
 
-        Ok(User {
+        let user = User {
             id: new_id,
             username: username.to_string(),
-            elo: 1000,
+            rating: DEFAULT_RATING,
+            rating_deviation: DEFAULT_RATING_DEVIATION,
+            volatility: DEFAULT_VOLATILITY,
+            last_played,
             wins: 0,
             losses: 0,
             bricks: 0,
             gold: 0,
-        })
+        };
+        self.user_cache.insert(new_id, user.clone()).await;
+        Ok(user)
     }
 
-    /// Update match result with ELO calculation and resource rewards
+    /// Update match result with a Glicko-2 rating update and resource
+    /// rewards. Each side's match is treated as its own one-opponent rating
+    /// period (see `glicko2_update`) rather than batching periodic updates,
+    /// since this server only ever has one result to apply at a time.
     pub async fn update_match_result(
         &self,
         winner_id: Uuid,
         loser_id: Uuid,
         is_tie: bool,
     ) -> Result<(User, User), sqlx::Error> {
+        // Re-inflate RD for time spent away before pulling the ratings this
+        // update actually works from, so a returning player's rating moves
+        // by more than someone who never stopped playing.
+        self.apply_inactivity_decay(winner_id).await?;
+        self.apply_inactivity_decay(loser_id).await?;
+
         // Get current stats for both players
         let winner = self.get_user_by_id(winner_id).await?;
         let loser = self.get_user_by_id(loser_id).await?;
 
-        // Calculate ELO changes using standard formula
-        let (winner_new_elo, loser_new_elo) = if is_tie {
-            // Tie - smaller ELO change
-            calculate_elo_change(winner.elo, loser.elo, 0.5)
-        } else {
-            // Winner gets full points
-            calculate_elo_change(winner.elo, loser.elo, 1.0)
-        };
+        let (winner_score, loser_score) = if is_tie { (0.5, 0.5) } else { (1.0, 0.0) };
+        let winner_new = glicko2_update(
+            &GlickoRating { rating: winner.rating, rd: winner.rating_deviation, volatility: winner.volatility },
+            &GlickoRating { rating: loser.rating, rd: loser.rating_deviation, volatility: loser.volatility },
+            winner_score,
+        );
+        let loser_new = glicko2_update(
+            &GlickoRating { rating: loser.rating, rd: loser.rating_deviation, volatility: loser.volatility },
+            &GlickoRating { rating: winner.rating, rd: winner.rating_deviation, volatility: winner.volatility },
+            loser_score,
+        );
 
         // Calculate resource rewards
         let (winner_bricks, winner_gold, loser_bricks, loser_gold) = if is_tie {
@@ -129,9 +307,11 @@ impl Database {
 
         // Update winner
         sqlx::query(
-            "UPDATE users SET elo = ?, wins = wins + ?, bricks = bricks + ?, gold = gold + ? WHERE id = ?"
+            "UPDATE users SET rating = ?, rating_deviation = ?, volatility = ?, wins = wins + ?, bricks = bricks + ?, gold = gold + ? WHERE id = ?"
         )
-        .bind(winner_new_elo)
+        .bind(winner_new.rating)
+        .bind(winner_new.rd)
+        .bind(winner_new.volatility)
         .bind(if is_tie { 0 } else { 1 })
         .bind(winner_bricks)
         .bind(winner_gold)
@@ -141,9 +321,11 @@ impl Database {
 
         // Update loser
         sqlx::query(
-            "UPDATE users SET elo = ?, losses = losses + ?, bricks = bricks + ?, gold = gold + ? WHERE id = ?"
+            "UPDATE users SET rating = ?, rating_deviation = ?, volatility = ?, losses = losses + ?, bricks = bricks + ?, gold = gold + ? WHERE id = ?"
         )
-        .bind(loser_new_elo)
+        .bind(loser_new.rating)
+        .bind(loser_new.rd)
+        .bind(loser_new.volatility)
         .bind(if is_tie { 0 } else { 1 })
         .bind(loser_bricks)
         .bind(loser_gold)
@@ -154,17 +336,60 @@ impl Database {
         // Commit transaction
         tx.commit().await?;
 
-        // Fetch updated stats
-        let winner_updated = self.get_user_by_id(winner_id).await?;
-        let loser_updated = self.get_user_by_id(loser_id).await?;
+        // The transaction already knows the new values, so build the
+        // updated `User`s from them directly instead of re-fetching - and
+        // write them straight into the cache so the `AuthAccepted`/
+        // `MatchResult` paths right after this call hit it instead of
+        // SQLite again.
+        let winner_updated = User {
+            rating: winner_new.rating,
+            rating_deviation: winner_new.rd,
+            volatility: winner_new.volatility,
+            wins: winner.wins + if is_tie { 0 } else { 1 },
+            bricks: winner.bricks + winner_bricks as u32,
+            gold: winner.gold + winner_gold as u32,
+            ..winner
+        };
+        let loser_updated = User {
+            rating: loser_new.rating,
+            rating_deviation: loser_new.rd,
+            volatility: loser_new.volatility,
+            losses: loser.losses + if is_tie { 0 } else { 1 },
+            bricks: loser.bricks + loser_bricks as u32,
+            gold: loser.gold + loser_gold as u32,
+            ..loser
+        };
+        self.user_cache.insert(winner_id, winner_updated.clone()).await;
+        self.user_cache.insert(loser_id, loser_updated.clone()).await;
 
         Ok((winner_updated, loser_updated))
     }
 
-    /// Get user by ID
-    async fn get_user_by_id(&self, id: Uuid) -> Result<User, sqlx::Error> {
+    // A prior commit (chunk6-6) added a team-event rating update here
+    // (`update_team_match_result`) without the 2v2 matchmaking, `GameSession`,
+    // or protocol changes needed to ever call it, and with a formula that
+    // didn't match what was asked for (per-member Glicko-2 against an
+    // averaged virtual opponent, instead of the requested
+    // `q_team = 10^(mean_team_rating/400)`, `E_team = q_team / Sum(q)`
+    // expected-score split). Landing unreachable rating math - atop a
+    // `GameSession`, lockstep, and matchmaking queue that are hard-coded to
+    // two participants throughout this server - isn't a safe partial step
+    // toward a real team mode, so it's removed here rather than patched in
+    // place. Team play is still just a backlog item, not a started one:
+    // it needs its own request covering `ClientMessage::JoinQueue { team_size }`,
+    // a `MatchFound` carrying teammate/opponent id lists, garbage/score
+    // relay split by side, *and* this rating formula, landed together.
+
+    /// Get user by ID. Served from `user_cache` when a fresh-enough entry
+    /// exists, so hot paths like `update_match_result`'s per-match fetches
+    /// and the leaderboard's per-viewer logins don't all hit SQLite.
+    pub async fn get_user_by_id(&self, id: Uuid) -> Result<User, sqlx::Error> {
+        if let Some(user) = self.user_cache.get(&id).await {
+            return Ok(user);
+        }
+
         let row = sqlx::query(
-            "SELECT id, username, elo, wins, losses, bricks, gold FROM users WHERE id = ?"
+            "SELECT id, username, rating, rating_deviation, volatility, last_played, wins, losses, bricks, gold FROM users WHERE id = ?"
         )
         .bind(id.to_string())
         .fetch_one(&self.pool)
@@ -173,47 +398,415 @@ impl Database {
         let id_str: String = row.get("id");
         let parsed_id = Uuid::parse_str(&id_str).unwrap();
 
-        Ok(User {
+        let user = User {
             id: parsed_id,
             username: row.get("username"),
-            elo: row.get("elo"),
+            rating: row.get("rating"),
+            rating_deviation: row.get("rating_deviation"),
+            volatility: row.get("volatility"),
+            last_played: row.get("last_played"),
             wins: row.get("wins"),
             losses: row.get("losses"),
             bricks: row.get("bricks"),
             gold: row.get("gold"),
-        })
+        };
+        self.user_cache.insert(id, user.clone()).await;
+        Ok(user)
+    }
+
+    /// Records a completed match. `winner_id` is `None` for a tie; scores
+    /// and rating changes are stored from player1's and player2's own
+    /// perspective and reordered relative to the requester in
+    /// `get_match_history`.
+    pub async fn record_match(
+        &self,
+        player1_id: Uuid,
+        player2_id: Uuid,
+        player1_score: u32,
+        player2_score: u32,
+        player1_rating_change: f64,
+        player2_rating_change: f64,
+        winner_id: Option<Uuid>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO match_history
+                (player1_id, player2_id, player1_score, player2_score, player1_rating_change, player2_rating_change, winner_id)
+             VALUES (?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(player1_id.to_string())
+        .bind(player2_id.to_string())
+        .bind(player1_score)
+        .bind(player2_score)
+        .bind(player1_rating_change)
+        .bind(player2_rating_change)
+        .bind(winner_id.map(|id| id.to_string()))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Fetches up to `limit` matches `player_id` took part in, newest first,
+    /// optionally starting strictly before `before` (a previous page's
+    /// `next_cursor`, the `match_history.id` of its oldest row). Returns the
+    /// page plus the cursor for the next page, `None` if this page was short
+    /// (nothing older left).
+    pub async fn get_match_history(
+        &self,
+        player_id: Uuid,
+        limit: u32,
+        before: Option<i64>,
+    ) -> Result<(Vec<MatchHistoryEntry>, Option<i64>), sqlx::Error> {
+        let player_str = player_id.to_string();
+        let rows = match before {
+            Some(cursor) => {
+                sqlx::query(
+                    "SELECT id, player1_id, player2_id, player1_score, player2_score,
+                            player1_rating_change, player2_rating_change, winner_id,
+                            CAST(strftime('%s', played_at) AS INTEGER) AS played_at
+                     FROM match_history
+                     WHERE (player1_id = ? OR player2_id = ?) AND id < ?
+                     ORDER BY id DESC LIMIT ?"
+                )
+                .bind(&player_str)
+                .bind(&player_str)
+                .bind(cursor)
+                .bind(limit as i64)
+                .fetch_all(&self.pool)
+                .await?
+            }
+            None => {
+                sqlx::query(
+                    "SELECT id, player1_id, player2_id, player1_score, player2_score,
+                            player1_rating_change, player2_rating_change, winner_id,
+                            CAST(strftime('%s', played_at) AS INTEGER) AS played_at
+                     FROM match_history
+                     WHERE (player1_id = ? OR player2_id = ?)
+                     ORDER BY id DESC LIMIT ?"
+                )
+                .bind(&player_str)
+                .bind(&player_str)
+                .bind(limit as i64)
+                .fetch_all(&self.pool)
+                .await?
+            }
+        };
+
+        let full_page = rows.len() as u32 == limit;
+        let entries: Vec<MatchHistoryEntry> = rows
+            .iter()
+            .map(|row| {
+                let player1_id: String = row.get("player1_id");
+                let player2_id: String = row.get("player2_id");
+                let winner_id: Option<String> = row.get("winner_id");
+                MatchHistoryEntry {
+                    id: row.get("id"),
+                    player1_id: Uuid::parse_str(&player1_id).unwrap(),
+                    player2_id: Uuid::parse_str(&player2_id).unwrap(),
+                    player1_score: row.get("player1_score"),
+                    player2_score: row.get("player2_score"),
+                    player1_rating_change: row.get("player1_rating_change"),
+                    player2_rating_change: row.get("player2_rating_change"),
+                    winner_id: winner_id.map(|s| Uuid::parse_str(&s).unwrap()),
+                    played_at: row.get("played_at"),
+                }
+            })
+            .collect();
+
+        let next_cursor = if full_page { entries.last().map(|e| e.id) } else { None };
+        Ok((entries, next_cursor))
+    }
+
+    /// Fetches up to `limit` matches between `player_a` and `player_b`,
+    /// newest first, plus `player_a`'s overall win/loss record against
+    /// `player_b` across their *entire* history (not just the returned
+    /// page), so the UI can show a rivalry record even when it's only
+    /// rendering a handful of recent games.
+    pub async fn get_head_to_head(
+        &self,
+        player_a: Uuid,
+        player_b: Uuid,
+        limit: u32,
+    ) -> Result<(Vec<MatchHistoryEntry>, u32, u32), sqlx::Error> {
+        let a_str = player_a.to_string();
+        let b_str = player_b.to_string();
+
+        let rows = sqlx::query(
+            "SELECT id, player1_id, player2_id, player1_score, player2_score,
+                    player1_rating_change, player2_rating_change, winner_id,
+                    CAST(strftime('%s', played_at) AS INTEGER) AS played_at
+             FROM match_history
+             WHERE (player1_id = ? AND player2_id = ?) OR (player1_id = ? AND player2_id = ?)
+             ORDER BY id DESC LIMIT ?"
+        )
+        .bind(&a_str)
+        .bind(&b_str)
+        .bind(&b_str)
+        .bind(&a_str)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let entries: Vec<MatchHistoryEntry> = rows
+            .iter()
+            .map(|row| {
+                let player1_id: String = row.get("player1_id");
+                let player2_id: String = row.get("player2_id");
+                let winner_id: Option<String> = row.get("winner_id");
+                MatchHistoryEntry {
+                    id: row.get("id"),
+                    player1_id: Uuid::parse_str(&player1_id).unwrap(),
+                    player2_id: Uuid::parse_str(&player2_id).unwrap(),
+                    player1_score: row.get("player1_score"),
+                    player2_score: row.get("player2_score"),
+                    player1_rating_change: row.get("player1_rating_change"),
+                    player2_rating_change: row.get("player2_rating_change"),
+                    winner_id: winner_id.map(|s| Uuid::parse_str(&s).unwrap()),
+                    played_at: row.get("played_at"),
+                }
+            })
+            .collect();
+
+        let record_row = sqlx::query(
+            "SELECT
+                SUM(CASE WHEN winner_id = ? THEN 1 ELSE 0 END) AS wins,
+                SUM(CASE WHEN winner_id = ? THEN 1 ELSE 0 END) AS losses
+             FROM match_history
+             WHERE (player1_id = ? AND player2_id = ?) OR (player1_id = ? AND player2_id = ?)"
+        )
+        .bind(&a_str)
+        .bind(&b_str)
+        .bind(&a_str)
+        .bind(&b_str)
+        .bind(&b_str)
+        .bind(&a_str)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let wins: Option<i64> = record_row.get("wins");
+        let losses: Option<i64> = record_row.get("losses");
+
+        Ok((entries, wins.unwrap_or(0) as u32, losses.unwrap_or(0) as u32))
+    }
+
+    /// Reads the `config` table's `decay_const_days` row, falling back to
+    /// `DEFAULT_DECAY_CONST_DAYS` if it's somehow missing (e.g. a row
+    /// deleted by hand).
+    async fn get_decay_const_days(&self) -> Result<f64, sqlx::Error> {
+        let row = sqlx::query("SELECT value FROM config WHERE key = 'decay_const_days'")
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row.map(|r| r.get("value")).unwrap_or(DEFAULT_DECAY_CONST_DAYS))
+    }
+
+    /// Re-inflates `user_id`'s rating deviation for time spent away, the
+    /// Glicko-2 way: φ ← √(φ² + t·σ²), where `t` is elapsed rating periods
+    /// (`decay_const_days` long each) since `last_played`. Capped at
+    /// `DEFAULT_RATING_DEVIATION`, since RD can never have been wider than a
+    /// brand-new account's to begin with. `t` is fractional, so this is a
+    /// no-op only when no time at all has passed since `last_played`.
+    pub async fn apply_inactivity_decay(&self, user_id: Uuid) -> Result<(), sqlx::Error> {
+        let user = self.get_user_by_id(user_id).await?;
+        let now = now_unix();
+        let decay_const_days = self.get_decay_const_days().await?;
+
+        let elapsed_days = (now - user.last_played) as f64 / 86400.0;
+        let elapsed_periods = elapsed_days / decay_const_days;
+        if elapsed_periods <= 0.0 {
+            return Ok(());
+        }
+
+        let phi = user.rating_deviation / GLICKO_SCALE;
+        let sigma = user.volatility;
+        let decayed_phi = (phi * phi + elapsed_periods * sigma * sigma).sqrt();
+        let decayed_rd = (GLICKO_SCALE * decayed_phi).min(DEFAULT_RATING_DEVIATION);
+
+        sqlx::query("UPDATE users SET rating_deviation = ?, last_played = ? WHERE id = ?")
+            .bind(decayed_rd)
+            .bind(now)
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await?;
+
+        self.user_cache
+            .insert(user_id, User { rating_deviation: decayed_rd, last_played: now, ..user })
+            .await;
+
+        Ok(())
     }
 
-    /// Get top 10 players by ELO for leaderboard
-    pub async fn get_leaderboard(&self) -> Result<Vec<(String, i32)>, sqlx::Error> {
+    /// Get top 10 players by rating for leaderboard
+    pub async fn get_leaderboard(&self) -> Result<Vec<(String, f64)>, sqlx::Error> {
         let rows = sqlx::query(
-            "SELECT username, elo FROM users ORDER BY elo DESC LIMIT 10"
+            "SELECT username, rating FROM users ORDER BY rating DESC LIMIT 10"
         )
         .fetch_all(&self.pool)
         .await?;
 
         let leaderboard = rows.iter().map(|row| {
             let username: String = row.get("username");
-            let elo: i32 = row.get("elo");
-            (username, elo)
+            let rating: f64 = row.get("rating");
+            (username, rating)
         }).collect();
 
         Ok(leaderboard)
     }
 }
 
-/// Calculate ELO change using standard formula
-/// K-factor = 32 (standard for chess)
-fn calculate_elo_change(winner_elo: i32, loser_elo: i32, score: f64) -> (i32, i32) {
-    const K: f64 = 32.0;
+/// Default rating (`r`) for a brand-new account - the Glicko-2 scale's
+/// center point, same as chess's 1500 USCF-ish convention.
+pub const DEFAULT_RATING: f64 = 1500.0;
+/// Default rating deviation (`RD`) for a brand-new account - wide, since
+/// nothing is known about them yet; narrows as they play (see
+/// `glicko2_update`).
+pub const DEFAULT_RATING_DEVIATION: f64 = 350.0;
+/// Default volatility (`σ`) for a brand-new account - Glickman's own
+/// suggested starting value.
+const DEFAULT_VOLATILITY: f64 = 0.06;
+
+/// Default length in days of a Glicko-2 "rating period" for decay purposes,
+/// used to seed the `config` table's `decay_const_days` row on first run.
+/// Overridable per-deployment by editing that row directly, since there's no
+/// admin command for it yet.
+const DEFAULT_DECAY_CONST_DAYS: f64 = 7.0;
+
+/// Seconds since the Unix epoch, used to stamp and diff `last_played`.
+fn now_unix() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+/// System constant `τ` constraining how much `volatility` can change per
+/// rating period - smaller values trust a player's historical volatility
+/// more. 0.5 is within Glickman's recommended 0.3-1.2 range.
+const GLICKO_TAU: f64 = 0.5;
+/// Conversion factor between the public rating scale (~1500-centered) and
+/// the internal Glicko-2 `μ`/`φ` scale every formula in `glicko2_update`
+/// actually operates on.
+const GLICKO_SCALE: f64 = 173.7178;
+/// How close successive regula-falsi iterates must get before accepting
+/// the solved volatility, in `glicko2_update`'s internal `ln(σ²)` units.
+const GLICKO_CONVERGENCE_TOLERANCE: f64 = 0.000001;
+
+/// A player's Glicko-2 rating triple, on the public (~1500-centered) scale
+/// - the same three fields `User` persists, grouped here so `glicko2_update`
+/// doesn't need five loose parameters.
+struct GlickoRating {
+    rating: f64,
+    rd: f64,
+    volatility: f64,
+}
+
+/// The Glicko-2 `g(φ)` de-weighting function: shrinks an opponent's
+/// influence on the outcome estimate the less certain their own rating is.
+fn glicko2_g(phi: f64) -> f64 {
+    1.0 / (1.0 + 3.0 * phi * phi / (std::f64::consts::PI * std::f64::consts::PI)).sqrt()
+}
+
+/// The Glicko-2 expected-score function for a player at `mu` facing an
+/// opponent at `mu_j`, already de-weighted by `g(phi_j)`.
+fn glicko2_e(mu: f64, mu_j: f64, g_phi_j: f64) -> f64 {
+    1.0 / (1.0 + (-g_phi_j * (mu - mu_j)).exp())
+}
 
-    // Expected scores
-    let winner_expected = 1.0 / (1.0 + 10_f64.powf((loser_elo - winner_elo) as f64 / 400.0));
-    let loser_expected = 1.0 - winner_expected;
+/// Computes `player`'s post-match Glicko-2 rating after a single result
+/// against `opponent`, per Glickman's Glicko-2 paper. `score` is this
+/// player's outcome: `1.0` win, `0.5` tie, `0.0` loss.
+///
+/// This server calls it once per side immediately after each match rather
+/// than batching a rating period's worth of games, so every call treats the
+/// match as a one-opponent period - there's no analogue here of the
+/// "player had zero games this period" case, where `φ* = √(φ² + σ²)` is the
+/// only update applied.
+fn glicko2_update(player: &GlickoRating, opponent: &GlickoRating, score: f64) -> GlickoRating {
+    let mu = (player.rating - 1500.0) / GLICKO_SCALE;
+    let phi = player.rd / GLICKO_SCALE;
+    let mu_j = (opponent.rating - 1500.0) / GLICKO_SCALE;
+    let phi_j = opponent.rd / GLICKO_SCALE;
 
-    // Calculate changes
-    let winner_change = (K * (score - winner_expected)).round() as i32;
-    let loser_change = (K * ((1.0 - score) - loser_expected)).round() as i32;
+    let g_phi_j = glicko2_g(phi_j);
+    let e = glicko2_e(mu, mu_j, g_phi_j);
+    let v = 1.0 / (g_phi_j * g_phi_j * e * (1.0 - e));
+    let delta = v * g_phi_j * (score - e);
+
+    // Solve f(x) = 0 for the new volatility via the Illinois variant of
+    // regula falsi, as specified in the Glicko-2 paper.
+    let a = (player.volatility * player.volatility).ln();
+    let f = |x: f64| {
+        let ex = x.exp();
+        let numerator = ex * (delta * delta - phi * phi - v - ex);
+        let denominator = 2.0 * (phi * phi + v + ex).powi(2);
+        numerator / denominator - (x - a) / (GLICKO_TAU * GLICKO_TAU)
+    };
+
+    let mut big_a = a;
+    let mut big_b = if delta * delta > phi * phi + v {
+        (delta * delta - phi * phi - v).ln()
+    } else {
+        let mut k = 1.0;
+        while f(a - k * GLICKO_TAU) < 0.0 {
+            k += 1.0;
+        }
+        a - k * GLICKO_TAU
+    };
+
+    let mut f_a = f(big_a);
+    let mut f_b = f(big_b);
+    while (big_b - big_a).abs() > GLICKO_CONVERGENCE_TOLERANCE {
+        let c = big_a + (big_a - big_b) * f_a / (f_b - f_a);
+        let f_c = f(c);
+        if f_c * f_b <= 0.0 {
+            big_a = big_b;
+            f_a = f_b;
+        } else {
+            f_a /= 2.0;
+        }
+        big_b = c;
+        f_b = f_c;
+    }
+
+    let new_volatility = (big_a / 2.0).exp();
+    let phi_star = (phi * phi + new_volatility * new_volatility).sqrt();
+    let new_phi = 1.0 / (1.0 / (phi_star * phi_star) + 1.0 / v).sqrt();
+    let new_mu = mu + new_phi * new_phi * g_phi_j * (score - e);
+
+    GlickoRating {
+        rating: GLICKO_SCALE * new_mu + 1500.0,
+        rd: GLICKO_SCALE * new_phi,
+        volatility: new_volatility,
+    }
+}
+
+/// Base width, in rating-deviations either side of `rating`, of a player's
+/// matchmaking confidence interval - the usual ~95%-ish Glicko band.
+const MATCHMAKING_BASE_RD_MULTIPLIER: f64 = 2.0;
+/// How much that multiplier grows per second spent waiting in queue, so a
+/// player who's waited a while accepts a wider range of opponents than one
+/// who just joined (see `confidence_intervals_overlap`).
+const MATCHMAKING_WIDEN_PER_SECOND: f64 = 0.02;
+
+/// Whether `(rating_a, rd_a)` and `(rating_b, rd_b)` are close enough to
+/// match, by the rule that their `rating ± k·RD` confidence intervals
+/// overlap. `waited_secs` is how long the longer-waiting of the two has
+/// been queued; `k` grows with it, so a gap that was too wide when they
+/// joined can become acceptable the longer nobody better shows up.
+/// Provisional (high-RD) players start with a wide band and so match
+/// broadly right away; established (low-RD) players only widen into a
+/// mismatch after a long wait.
+pub fn confidence_intervals_overlap(rating_a: f64, rd_a: f64, rating_b: f64, rd_b: f64, waited_secs: f64) -> bool {
+    let k = MATCHMAKING_BASE_RD_MULTIPLIER + MATCHMAKING_WIDEN_PER_SECOND * waited_secs;
+    let (lo_a, hi_a) = (rating_a - k * rd_a, rating_a + k * rd_a);
+    let (lo_b, hi_b) = (rating_b - k * rd_b, rating_b + k * rd_b);
+    lo_a <= hi_b && lo_b <= hi_a
+}
 
-    (winner_elo + winner_change, loser_elo + loser_change)
+/// The (non-Glicko-2, "plain" Glicko) expected score of a player rated
+/// `rating` against an opponent rated `opponent_rating`: the probability
+/// they win, ignoring both sides' `RD`. Used to report a rough win estimate
+/// to the client at `MatchFound` time, before anything about this specific
+/// match has actually been played.
+pub fn expected_score(rating: f64, opponent_rating: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((opponent_rating - rating) / 400.0))
 }
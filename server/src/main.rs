@@ -1,281 +1,799 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
 use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock, Mutex};
+use std::time::Instant;
+use tokio::sync::{mpsc, oneshot, RwLock, Mutex};
 use tokio::time::{interval, Duration};
 use futures::{StreamExt, SinkExt};
 use tokio_tungstenite::tungstenite::Message;
+use tracing::Instrument;
 use uuid::Uuid;
-use match3_protocol::{ClientMessage, ServerMessage, GameResult, PlayerId, GameId};
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use match3_protocol::{
+    ClientMessage, ServerMessage, GameResult, MatchRecord, PlayerId, GameId,
+    PROTOCOL_VERSION, MIN_SUPPORTED_PROTOCOL_VERSION,
+};
 
 mod db;
-use db::Database;
+use db::{AuthDbError, Database};
+
+mod cluster;
+use cluster::{ClusterClient, ClusterConfig};
+
+mod metrics;
+use metrics::Metrics;
 
 type Tx = mpsc::UnboundedSender<Message>;
 
 const GAME_DURATION: u64 = 90; // seconds
 
-// Player representation
+/// Cap on how many recent matches `request_head_to_head` returns per
+/// request - a rivalry view doesn't need pagination the way the full
+/// `RequestHistory` one does.
+const HEAD_TO_HEAD_LIMIT: u32 = 20;
+
+// How long a disconnected player's opponent waits - with the match timer
+// paused - before the match is finalized as a forfeit.
+const RECONNECT_GRACE: Duration = Duration::from_secs(20);
+
+// How long `run_game_actor` keeps a finished match's entries alive after
+// `end_match`, waiting for a `GameCommand::Rematch` before tearing the
+// actor down - long enough for both players to see the result and decide,
+// short enough that a match nobody returns to doesn't linger forever.
+const POST_MATCH_CLEANUP_GRACE: Duration = Duration::from_secs(30);
+
+/// Hashes `password` into PHC string format with a freshly-generated salt.
+/// Argon2 is deliberately slow, so the caller must run this on
+/// `spawn_blocking` rather than the async executor.
+fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error> {
+    let salt = SaltString::generate(&mut OsRng);
+    Ok(Argon2::default().hash_password(password.as_bytes(), &salt)?.to_string())
+}
+
+/// Checks `password` against a stored PHC hash. Returns `false` (rather than
+/// an error) for a malformed stored hash, since that should never block a
+/// login attempt from failing cleanly.
+fn verify_password(password: &str, hash: &str) -> bool {
+    let Ok(parsed) = PasswordHash::new(hash) else { return false };
+    Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok()
+}
+
+// In-match chat flood-protection thresholds; see `FloodState::check_and_record`.
+const CHAT_FLOOD_WINDOW_SECS: f32 = 4.0;
+const CHAT_FLOOD_MAX_MESSAGES: usize = 4;
+const CHAT_FLOOD_PENALTY_SECS: f32 = 10.0;
+
+/// Per-player sliding-window flood guard for in-match chat: keeps the
+/// timestamps of recent messages still inside `CHAT_FLOOD_WINDOW_SECS`, plus
+/// a lockout end time once the sender has been muted for exceeding
+/// `CHAT_FLOOD_MAX_MESSAGES`.
+#[derive(Debug, Default)]
+struct FloodState {
+    recent: VecDeque<Instant>,
+    locked_until: Option<Instant>,
+}
+
+impl FloodState {
+    /// Accepts and records `now`'s message, or rejects it and returns how
+    /// many seconds the sender is muted for.
+    fn check_and_record(&mut self, now: Instant) -> Result<(), f32> {
+        if let Some(until) = self.locked_until {
+            if now < until {
+                return Err((until - now).as_secs_f32());
+            }
+            self.locked_until = None;
+        }
+
+        let window_start = now - Duration::from_secs_f32(CHAT_FLOOD_WINDOW_SECS);
+        self.recent.retain(|&t| t >= window_start);
+
+        if self.recent.len() >= CHAT_FLOOD_MAX_MESSAGES {
+            self.locked_until = Some(now + Duration::from_secs_f32(CHAT_FLOOD_PENALTY_SECS));
+            return Err(CHAT_FLOOD_PENALTY_SECS);
+        }
+
+        self.recent.push_back(now);
+        Ok(())
+    }
+}
+
+// Player representation. `tx` is behind a lock so a reconnect can swap in a
+// fresh sender without invalidating clones already held elsewhere (e.g. a
+// game's actor task, or `ServerState.players`).
 #[derive(Debug, Clone)]
 struct Player {
     id: PlayerId,
-    tx: Tx,
+    tx: Arc<RwLock<Tx>>,
+}
+
+/// Everything that can go wrong delivering a message to a player. Kept
+/// narrow on purpose: both variants mean "this send didn't happen", which
+/// is all any caller needs to decide whether to keep going or treat the
+/// player as disconnected.
+#[derive(Debug, thiserror::Error)]
+enum ServerError {
+    #[error("failed to serialize outgoing message: {0}")]
+    Serialize(#[from] serde_json::Error),
+    #[error("player's connection is gone")]
+    Disconnected,
+}
+
+impl From<mpsc::error::SendError<Message>> for ServerError {
+    fn from(_: mpsc::error::SendError<Message>) -> Self {
+        // The error only ever carries the message we failed to send back,
+        // which isn't worth holding onto - the channel being closed means
+        // the receiving task (and so the whole connection) is already gone.
+        ServerError::Disconnected
+    }
 }
 
-// Game Session
-#[derive(Debug)]
+/// Serializes `msg` once and sends it to `player`. An `Err` means their
+/// receiving task - and so their whole connection - is gone; callers in the
+/// middle of a live match use that to trigger `GameSession::handle_disconnect`
+/// promptly instead of only noticing once `GAME_DURATION` elapses.
+async fn send_to(player: &Player, msg: &ServerMessage) -> Result<(), ServerError> {
+    let json = serde_json::to_string(msg)?;
+    player.tx.read().await.send(Message::Text(json))?;
+    Ok(())
+}
+
+/// A cached `BoardUpdate`, replayed to a resuming opponent via
+/// `ServerMessage::BoardSnapshot` when their `known_board_version` is stale.
+#[derive(Debug, Clone)]
+struct BoardCache {
+    grid: Vec<u8>,
+    score: u32,
+    energy: u32,
+}
+
+/// Everything `GameCommand::Resume` hands back: the (possibly tx-swapped)
+/// `Player` for `ServerState::resume_player` to reinsert into
+/// `ServerState.players`, plus the data needed for `ResumeAccepted` and a
+/// board resync.
+struct ResumeResult {
+    player: Player,
+    game_id: GameId,
+    seconds_remaining: u64,
+    player_score: u32,
+    opponent_score: u32,
+    pending_garbage: u8,
+    board_version: u64,
+    opponent_board: Option<BoardCache>,
+}
+
+/// One command per client action that touches a match's state. A game's
+/// actor task (see `run_game_actor`) processes these serially against its
+/// own owned `GameSession` fields - no `RwLock` anywhere in it - so a
+/// rematch can never race the timer tick that's ending the match it's
+/// replacing, and a score write can never interleave with the timer's
+/// end-of-game read.
+enum GameCommand {
+    Swap { from_player: PlayerId, row1: usize, col1: usize, row2: usize, col2: usize },
+    Score { player_id: PlayerId, new_score: u32 },
+    Garbage { from_player: PlayerId, amount: u8 },
+    Special { from_player: PlayerId, row: usize, col: usize },
+    Booster { from_player: PlayerId, booster_id: u8 },
+    Emote { from_player: PlayerId, emote_id: u8 },
+    BoardUpdate { from_player: PlayerId, grid: Vec<u8>, score: u32, energy: u32 },
+    InputFrame { from_player: PlayerId, frame: u32, inputs: Vec<u8> },
+    StateChecksum { from_player: PlayerId, frame: u32, hash: u64 },
+    Chat { from_player: PlayerId, from_name: String, text: String },
+    Spectate { player: Player },
+    Rematch { player_id: PlayerId },
+    Leave { player_id: PlayerId },
+    /// Unlike `Leave`, ends the match immediately as a forfeit loss for
+    /// `player_id` - same path as a reconnect-grace timeout in
+    /// `run_game_actor_inner` - rather than just notifying the opponent.
+    Forfeit { player_id: PlayerId },
+    Disconnect { player_id: PlayerId },
+    Resume { player_id: PlayerId, new_tx: Tx, known_board_version: u64, respond_to: oneshot::Sender<Option<ResumeResult>> },
+    GetStatus { respond_to: oneshot::Sender<bool> },
+}
+
+/// A running match's state, owned exclusively by its actor task (see
+/// `run_game_actor`) - nothing outside that task ever touches these fields,
+/// so none of them need a lock. `ServerState` only ever holds a `GameHandle`
+/// (a `GameCommand` sender) for it.
 struct GameSession {
     id: GameId,
     player1: Player,
     player2: Player,
-    scores: Arc<RwLock<(u32, u32)>>, // (player1_score, player2_score)
-    start_time: std::time::Instant,
-    active: Arc<RwLock<bool>>,
-    rematch_requests: Arc<RwLock<(bool, bool)>>, // (player1_requested, player2_requested)
+    scores: (u32, u32), // (player1_score, player2_score)
+    start_time: Instant,
+    active: bool,
+    rematch_requests: (bool, bool), // (player1_requested, player2_requested)
+    // Shared board seed so both clients (and a future replay-checking server
+    // match loop) derive byte-identical grids from the same RNG stream.
+    // Re-rolled on every rematch so the new match doesn't replay the first
+    // one's board.
+    seed: u64,
+    // Garbage relayed to each player since their last resume, in case they
+    // drop and reconnect before applying it: (player1_pending, player2_pending).
+    pending_garbage: (u8, u8),
+    // Players registered as spectators via `SpectateGame`; `BoardSnapshot`
+    // broadcasts go to these in addition to the sending player's opponent.
+    spectators: Vec<Player>,
+    // Flood-protection state for in-match chat: (player1, player2).
+    chat_flood: (FloodState, FloodState),
+    // Bumped every time either player's board is relayed via `BoardUpdate`,
+    // so a reconnecting client can report the version it last applied and
+    // skip re-fetching the opponent's grid when nothing changed.
+    board_version: u64,
+    // Last board each player reported: (player1's last board, player2's
+    // last board). Replayed to a
+    // resuming opponent instead of waiting for the next live `BoardUpdate`.
+    last_boards: (Option<BoardCache>, Option<BoardCache>),
+    // When each player's transport last dropped, if it's still down:
+    // (player1, player2). `run_game_actor` freezes the countdown while
+    // either is `Some`, and finalizes the match as a forfeit once one has
+    // been detached longer than `RECONNECT_GRACE`.
+    detached_since: (Option<Instant>, Option<Instant>),
 }
 
 impl GameSession {
     fn new(player1: Player, player2: Player) -> Self {
-        let game_id = Uuid::new_v4();
-
         Self {
-            id: game_id,
+            id: Uuid::new_v4(),
             player1,
             player2,
-            scores: Arc::new(RwLock::new((0, 0))),
-            start_time: std::time::Instant::now(),
-            active: Arc::new(RwLock::new(true)),
-            rematch_requests: Arc::new(RwLock::new((false, false))),
+            scores: (0, 0),
+            start_time: Instant::now(),
+            active: true,
+            rematch_requests: (false, false),
+            seed: ::rand::random::<u64>(),
+            pending_garbage: (0, 0),
+            spectators: Vec::new(),
+            chat_flood: (FloodState::default(), FloodState::default()),
+            board_version: 0,
+            last_boards: (None, None),
+            detached_since: (None, None),
         }
     }
 
-    async fn start(&self, db: Database) {
-        // Notify both players that the game has started
-        let start_msg = ServerMessage::GameStarted { game_id: self.id };
-        let _ = self.player1.tx.send(Message::Text(
-            serde_json::to_string(&start_msg).unwrap()
-        ));
-        let _ = self.player2.tx.send(Message::Text(
-            serde_json::to_string(&start_msg).unwrap()
-        ));
-
-        // Start the game timer
-        self.run_game_timer(db).await;
+    fn opponent_of(&self, player_id: PlayerId) -> &Player {
+        if player_id == self.player1.id { &self.player2 } else { &self.player1 }
     }
 
-    async fn run_game_timer(&self, db: Database) {
-        let mut ticker = interval(Duration::from_secs(1));
-        let scores = Arc::clone(&self.scores);
-        let active = Arc::clone(&self.active);
-        let p1_tx = self.player1.tx.clone();
-        let p2_tx = self.player2.tx.clone();
-        let p1_id = self.player1.id;
-        let p2_id = self.player2.id;
+    /// Sends `msg` to whichever of `player1`/`player2` has `player_id`; if
+    /// their connection turns out to be gone, runs the same
+    /// `handle_disconnect` path a `GameCommand::Disconnect` would, so a dead
+    /// socket is caught the moment something tries to use it rather than
+    /// only once `GAME_DURATION` (or the reconnect grace period) elapses.
+    async fn send_or_mark_disconnected(&mut self, player_id: PlayerId, msg: &ServerMessage) {
+        let player = if player_id == self.player1.id { self.player1.clone() } else { self.player2.clone() };
+        if send_to(&player, msg).await.is_err() {
+            self.handle_disconnect(player_id).await;
+        }
+    }
 
-        tokio::spawn(async move {
-            for i in 0..GAME_DURATION {
-                ticker.tick().await;
+    /// Relays a swap; named in the same breath as `handle_garbage`/
+    /// `handle_special`/`handle_booster` as one of the hot in-match relays
+    /// that should notice a dead opponent socket immediately rather than
+    /// waiting for the next tick or timeout (see `send_or_mark_disconnected`).
+    async fn handle_swap(&mut self, from_player: PlayerId, row1: usize, col1: usize, row2: usize, col2: usize) {
+        let opponent_id = self.opponent_of(from_player).id;
+        let msg = ServerMessage::OpponentSwap { row1, col1, row2, col2 };
+        self.send_or_mark_disconnected(opponent_id, &msg).await;
+    }
 
-                let is_active = *active.read().await;
-                if !is_active {
-                    break;
-                }
+    async fn handle_special(&mut self, from_player: PlayerId, row: usize, col: usize) {
+        let opponent_id = self.opponent_of(from_player).id;
+        let msg = ServerMessage::OpponentActivatedSpecial { row, col };
+        self.send_or_mark_disconnected(opponent_id, &msg).await;
+    }
 
-                let remaining = GAME_DURATION - i - 1;
-                let time_msg = ServerMessage::TimeUpdate {
-                    seconds_remaining: remaining
-                };
-                let time_str = serde_json::to_string(&time_msg).unwrap();
+    async fn handle_booster(&mut self, from_player: PlayerId, booster_id: u8) {
+        let opponent_id = self.opponent_of(from_player).id;
+        let msg = ServerMessage::OpponentActivatedBooster { booster_id };
+        self.send_or_mark_disconnected(opponent_id, &msg).await;
+    }
 
-                let _ = p1_tx.send(Message::Text(time_str.clone()));
-                let _ = p2_tx.send(Message::Text(time_str));
-            }
+    async fn handle_emote(&self, from_player: PlayerId, emote_id: u8) {
+        let msg = ServerMessage::OpponentEmote { emote_id };
+        let _ = send_to(self.opponent_of(from_player), &msg).await;
+    }
 
-            // Game ended - determine winner
-            let (score1, score2) = *scores.read().await;
+    async fn handle_input_frame(&self, from_player: PlayerId, frame: u32, inputs: Vec<u8>) {
+        let msg = ServerMessage::OpponentInputFrame { frame, inputs };
+        let _ = send_to(self.opponent_of(from_player), &msg).await;
+    }
 
-            let p1_result = if score1 > score2 {
-                GameResult::Win
-            } else if score1 < score2 {
-                GameResult::Loss
-            } else {
-                GameResult::Tie
-            };
+    async fn handle_state_checksum(&self, from_player: PlayerId, frame: u32, hash: u64) {
+        let msg = ServerMessage::OpponentStateChecksum { frame, hash };
+        let _ = send_to(self.opponent_of(from_player), &msg).await;
+    }
 
-            let p2_result = if score2 > score1 {
-                GameResult::Win
-            } else if score2 < score1 {
-                GameResult::Loss
-            } else {
-                GameResult::Tie
-            };
-
-            let p1_msg = ServerMessage::GameOver { winner: p1_result.clone() };
-            let p2_msg = ServerMessage::GameOver { winner: p2_result.clone() };
-
-            let _ = p1_tx.send(Message::Text(serde_json::to_string(&p1_msg).unwrap()));
-            let _ = p2_tx.send(Message::Text(serde_json::to_string(&p2_msg).unwrap()));
-
-            // Update ELO ratings in database
-            let is_tie = p1_result == GameResult::Tie;
-            if let Ok((p1_updated, p2_updated)) = db.update_match_result(
-                p1_id,
-                p2_id,
-                is_tie,
-            ).await {
-                // Send match result with new ELO to both players
-                let p1_elo_change = p1_updated.elo - 1000; // We don't have old ELO, so approximate
-                let p2_elo_change = p2_updated.elo - 1000;
-
-                // For a more accurate calculation, we should store old ELO before the match
-                // For now, just send the new values
-                let p1_result_msg = ServerMessage::MatchResult {
-                    new_elo: p1_updated.elo,
-                    elo_change: p1_elo_change,
-                    wins: p1_updated.wins,
-                    losses: p1_updated.losses,
-                };
-                let p2_result_msg = ServerMessage::MatchResult {
-                    new_elo: p2_updated.elo,
-                    elo_change: p2_elo_change,
-                    wins: p2_updated.wins,
-                    losses: p2_updated.losses,
-                };
+    /// Tracks `amount` as pending in case the opponent drops and resumes
+    /// before this reaches them, then relays it live.
+    async fn handle_garbage(&mut self, from_player: PlayerId, amount: u8) {
+        if from_player == self.player1.id {
+            self.pending_garbage.1 = self.pending_garbage.1.saturating_add(amount);
+        } else {
+            self.pending_garbage.0 = self.pending_garbage.0.saturating_add(amount);
+        }
+        let opponent_id = self.opponent_of(from_player).id;
+        let msg = ServerMessage::ReceiveGarbage { amount };
+        self.send_or_mark_disconnected(opponent_id, &msg).await;
+    }
 
-                let _ = p1_tx.send(Message::Text(serde_json::to_string(&p1_result_msg).unwrap()));
-                let _ = p2_tx.send(Message::Text(serde_json::to_string(&p2_result_msg).unwrap()));
+    /// Runs `text` from `from_player` through this match's per-sender flood
+    /// guard; on success, broadcasts `ServerMessage::Chat` to both players
+    /// (including the sender, as an echo). On flood rejection, tells only
+    /// the sender how much longer they're muted for.
+    async fn handle_chat(&mut self, from_player: PlayerId, from_name: String, text: String) {
+        let is_player1 = from_player == self.player1.id;
+        let now = Instant::now();
+        let result = if is_player1 {
+            self.chat_flood.0.check_and_record(now)
+        } else {
+            self.chat_flood.1.check_and_record(now)
+        };
 
-                println!("Match result: {} (ELO: {}) vs {} (ELO: {})",
-                    p1_id, p1_updated.elo, p2_id, p2_updated.elo);
+        match result {
+            Err(seconds_remaining) => {
+                let notice = ServerMessage::Error {
+                    message: format!("You can't talk for {:.0} more seconds", seconds_remaining.ceil()),
+                };
+                let sender = if is_player1 { &self.player1 } else { &self.player2 };
+                let _ = send_to(sender, &notice).await;
             }
-
-            *active.write().await = false;
-        });
+            Ok(()) => {
+                let chat_msg = ServerMessage::Chat { from: from_name, text };
+                let _ = send_to(&self.player1, &chat_msg).await;
+                let _ = send_to(&self.player2, &chat_msg).await;
+            }
+        }
     }
 
-    async fn handle_swap(&self, from_player: PlayerId, row1: usize, col1: usize, row2: usize, col2: usize) {
-        // Notify the opponent about the swap
-        let swap_msg = ServerMessage::OpponentSwap { row1, col1, row2, col2 };
-        let swap_str = serde_json::to_string(&swap_msg).unwrap();
+    /// Relays a playing client's board to its opponent (so `opponent_grid`
+    /// can render it) and to every registered spectator.
+    async fn handle_board_update(&mut self, from_player: PlayerId, grid: Vec<u8>, score: u32, energy: u32) {
+        self.board_version += 1;
+        let version = self.board_version;
 
+        let cache = BoardCache { grid: grid.clone(), score, energy };
         if from_player == self.player1.id {
-            let _ = self.player2.tx.send(Message::Text(swap_str));
+            self.last_boards.0 = Some(cache);
         } else {
-            let _ = self.player1.tx.send(Message::Text(swap_str));
+            self.last_boards.1 = Some(cache);
+        }
+
+        let snapshot_msg = ServerMessage::BoardSnapshot { grid, score, energy, version };
+        let _ = send_to(self.opponent_of(from_player), &snapshot_msg).await;
+        for spectator in &self.spectators {
+            let _ = send_to(spectator, &snapshot_msg).await;
         }
     }
 
-    async fn update_score(&self, player_id: PlayerId, new_score: u32) {
-        let mut scores = self.scores.write().await;
+    /// Registers `player` as a spectator, if not already one. Idempotent so
+    /// a client can safely resend `SpectateGame`.
+    fn add_spectator(&mut self, player: Player) {
+        if !self.spectators.iter().any(|p| p.id == player.id) {
+            self.spectators.push(player);
+        }
+    }
 
+    async fn update_score(&mut self, player_id: PlayerId, new_score: u32) {
         if player_id == self.player1.id {
-            scores.0 = new_score;
-
-            // Send score update to both players
-            let msg1 = ServerMessage::ScoreUpdate {
-                player_score: scores.0,
-                opponent_score: scores.1
-            };
-            let msg2 = ServerMessage::ScoreUpdate {
-                player_score: scores.1,
-                opponent_score: scores.0
-            };
-
-            let _ = self.player1.tx.send(Message::Text(serde_json::to_string(&msg1).unwrap()));
-            let _ = self.player2.tx.send(Message::Text(serde_json::to_string(&msg2).unwrap()));
+            self.scores.0 = new_score;
         } else {
-            scores.1 = new_score;
+            self.scores.1 = new_score;
+        }
 
-            let msg1 = ServerMessage::ScoreUpdate {
-                player_score: scores.0,
-                opponent_score: scores.1
-            };
-            let msg2 = ServerMessage::ScoreUpdate {
-                player_score: scores.1,
-                opponent_score: scores.0
-            };
+        let msg1 = ServerMessage::ScoreUpdate { player_score: self.scores.0, opponent_score: self.scores.1 };
+        let msg2 = ServerMessage::ScoreUpdate { player_score: self.scores.1, opponent_score: self.scores.0 };
+        let (p1_id, p2_id) = (self.player1.id, self.player2.id);
+        self.send_or_mark_disconnected(p1_id, &msg1).await;
+        self.send_or_mark_disconnected(p2_id, &msg2).await;
+    }
 
-            let _ = self.player1.tx.send(Message::Text(serde_json::to_string(&msg1).unwrap()));
-            let _ = self.player2.tx.send(Message::Text(serde_json::to_string(&msg2).unwrap()));
+    async fn handle_disconnect(&mut self, player_id: PlayerId) {
+        // Leave `active` untouched: the socket dropping doesn't end the
+        // match. It pauses the countdown (see `is_paused`/`run_game_actor`)
+        // and starts this player's grace-period clock; they resume where
+        // they left off if `Resume` arrives before `RECONNECT_GRACE` is up.
+        if player_id == self.player1.id {
+            self.detached_since.0 = Some(Instant::now());
+        } else {
+            self.detached_since.1 = Some(Instant::now());
         }
+
+        let msg = ServerMessage::OpponentDisconnected { grace_seconds: RECONNECT_GRACE.as_secs() };
+        let _ = send_to(self.opponent_of(player_id), &msg).await;
     }
 
-    async fn handle_disconnect(&self, player_id: PlayerId) {
-        *self.active.write().await = false;
+    /// True while either player is between a dropped transport and a
+    /// `Resume` (or forfeit) - the countdown doesn't tick during this.
+    fn is_paused(&self) -> bool {
+        self.detached_since.0.is_some() || self.detached_since.1.is_some()
+    }
 
-        let disconnect_msg = ServerMessage::OpponentDisconnected;
-        let disconnect_str = serde_json::to_string(&disconnect_msg).unwrap();
+    /// The id of whichever player has been detached longer than `grace`, if
+    /// any - the one `run_game_actor` should finalize the match against.
+    fn timed_out_player(&self, grace: Duration) -> Option<PlayerId> {
+        if self.detached_since.0.is_some_and(|since| since.elapsed() >= grace) {
+            return Some(self.player1.id);
+        }
+        if self.detached_since.1.is_some_and(|since| since.elapsed() >= grace) {
+            return Some(self.player2.id);
+        }
+        None
+    }
 
-        // Notify the other player
+    async fn handle_leave(&mut self, player_id: PlayerId) {
+        self.active = false;
+        let msg = ServerMessage::OpponentLeft;
+        let _ = send_to(self.opponent_of(player_id), &msg).await;
+    }
+
+    /// Marks `player_id` as wanting a rematch and, once both have, resets
+    /// match state and re-rolls the seed. Returns whether the rematch just
+    /// started, so the caller (`run_game_actor`) knows to restart its
+    /// countdown - in the same loop, rather than spawning a new timer task.
+    async fn handle_rematch_request(&mut self, player_id: PlayerId) -> bool {
         if player_id == self.player1.id {
-            let _ = self.player2.tx.send(Message::Text(disconnect_str));
+            self.rematch_requests.0 = true;
+            let _ = send_to(&self.player2, &ServerMessage::OpponentRequestedRematch).await;
         } else {
-            let _ = self.player1.tx.send(Message::Text(disconnect_str));
+            self.rematch_requests.1 = true;
+            let _ = send_to(&self.player1, &ServerMessage::OpponentRequestedRematch).await;
         }
+
+        if !(self.rematch_requests.0 && self.rematch_requests.1) {
+            return false;
+        }
+
+        self.rematch_requests = (false, false);
+        self.scores = (0, 0);
+        self.active = true;
+        self.pending_garbage = (0, 0);
+        self.start_time = Instant::now();
+        // Re-roll the board seed so the rematch doesn't replay the same
+        // grid; both clients reseed their StdRng from this on receipt.
+        self.seed = ::rand::random::<u64>();
+
+        let msg = ServerMessage::RematchAccepted { seed: self.seed };
+        let _ = send_to(&self.player1, &msg).await;
+        let _ = send_to(&self.player2, &msg).await;
+        true
     }
 
-    async fn handle_rematch_request(&self, player_id: PlayerId, db: Database) {
-        let mut rematch_requests = self.rematch_requests.write().await;
+    /// Swaps in `new_tx` for `player_id` and builds the full `Resume`
+    /// response: rehydration data, plus - if the opponent's board is newer
+    /// than the client's cached `known_board_version` - a resync snapshot.
+    /// `elapsed_secs` is `run_game_actor`'s own countdown counter rather than
+    /// `self.start_time.elapsed()`, since the latter keeps advancing during
+    /// a pause while the countdown itself doesn't.
+    ///
+    /// Returns `None` if the match has already ended - e.g. a `Resume`
+    /// landing during `POST_MATCH_CLEANUP_GRACE`, after `end_match` but
+    /// before the actor tears down - so the caller can answer
+    /// `ResumeRejected` instead of rehydrating a client into a dead match.
+    async fn handle_resume(&mut self, player_id: PlayerId, new_tx: Tx, known_board_version: u64, elapsed_secs: u64) -> Option<ResumeResult> {
+        if !self.active {
+            return None;
+        }
 
-        // Mark this player as requesting rematch
-        if player_id == self.player1.id {
-            rematch_requests.0 = true;
+        let player = if player_id == self.player1.id { &self.player1 } else { &self.player2 };
+        *player.tx.write().await = new_tx;
+        let player = player.clone();
 
-            // Notify opponent
-            let msg = ServerMessage::OpponentRequestedRematch;
-            let _ = self.player2.tx.send(Message::Text(serde_json::to_string(&msg).unwrap()));
+        // Clear this player's grace-period clock and let the opponent know
+        // the countdown is unpaused, if they were ever told it paused.
+        let was_detached = if player_id == self.player1.id {
+            self.detached_since.0.take()
         } else {
-            rematch_requests.1 = true;
-
-            // Notify opponent
-            let msg = ServerMessage::OpponentRequestedRematch;
-            let _ = self.player1.tx.send(Message::Text(serde_json::to_string(&msg).unwrap()));
+            self.detached_since.1.take()
+        };
+        if was_detached.is_some() {
+            let _ = send_to(self.opponent_of(player_id), &ServerMessage::OpponentReconnected).await;
         }
 
-        // Check if both players have requested rematch
-        if rematch_requests.0 && rematch_requests.1 {
-            // Reset rematch requests
-            rematch_requests.0 = false;
-            rematch_requests.1 = false;
-            drop(rematch_requests);
+        let seconds_remaining = GAME_DURATION.saturating_sub(elapsed_secs);
+        let (player_score, opponent_score) = if player_id == self.player1.id {
+            (self.scores.0, self.scores.1)
+        } else {
+            (self.scores.1, self.scores.0)
+        };
 
-            // Reset game state
-            *self.scores.write().await = (0, 0);
-            *self.active.write().await = true;
+        let pending_garbage = if player_id == self.player1.id {
+            std::mem::take(&mut self.pending_garbage.0)
+        } else {
+            std::mem::take(&mut self.pending_garbage.1)
+        };
+
+        // Only hand back the opponent's (potentially large) grid if the
+        // client's cached copy is stale; otherwise the caller answers with
+        // a cheap `BoardUnchanged` instead of re-transmitting and
+        // re-decoding it on every reconnect.
+        let opponent_board = if known_board_version != self.board_version {
+            if player_id == self.player1.id { self.last_boards.1.clone() } else { self.last_boards.0.clone() }
+        } else {
+            None
+        };
+
+        Some(ResumeResult {
+            player,
+            game_id: self.id,
+            seconds_remaining,
+            player_score,
+            opponent_score,
+            pending_garbage,
+            board_version: self.board_version,
+            opponent_board,
+        })
+    }
+}
 
-            // Notify both players that rematch is accepted
-            let msg = ServerMessage::RematchAccepted;
-            let msg_str = serde_json::to_string(&msg).unwrap();
-            let _ = self.player1.tx.send(Message::Text(msg_str.clone()));
-            let _ = self.player2.tx.send(Message::Text(msg_str));
+/// Determines the winner from final scores (or, if `forfeited_by` is set,
+/// from that instead - see `GameSession::timed_out_player`), notifies both
+/// players, updates ratings in the database, and marks the match inactive.
+/// Called by `run_game_actor` when its countdown reaches zero or a
+/// disconnected player's reconnection grace period expires.
+async fn end_match(
+    session: &mut GameSession,
+    db: &Database,
+    leaderboard_version: &Arc<RwLock<u64>>,
+    metrics: &Metrics,
+    forfeited_by: Option<PlayerId>,
+) {
+    let (score1, score2) = session.scores;
+
+    let (p1_result, p2_result) = match forfeited_by {
+        Some(id) if id == session.player1.id => (GameResult::Loss, GameResult::Win),
+        Some(_) => (GameResult::Win, GameResult::Loss),
+        None if score1 > score2 => (GameResult::Win, GameResult::Loss),
+        None if score1 < score2 => (GameResult::Loss, GameResult::Win),
+        None => (GameResult::Tie, GameResult::Tie),
+    };
 
-            // Start a new game
-            self.run_game_timer(db).await;
+    let _ = send_to(&session.player1, &ServerMessage::GameOver { winner: p1_result.clone() }).await;
+    let _ = send_to(&session.player2, &ServerMessage::GameOver { winner: p2_result.clone() }).await;
+
+    let is_tie = p1_result == GameResult::Tie;
+
+    // Capture pre-match rating so the change we report and persist is
+    // exact, not approximated against the 1500 starting rating.
+    let p1_pre_rating = db.get_user_by_id(session.player1.id).await.map(|u| u.rating).ok();
+    let p2_pre_rating = db.get_user_by_id(session.player2.id).await.map(|u| u.rating).ok();
+
+    if let Ok((p1_updated, p2_updated)) = db.update_match_result(session.player1.id, session.player2.id, is_tie).await {
+        let p1_rating_change = p1_updated.rating - p1_pre_rating.unwrap_or(p1_updated.rating);
+        let p2_rating_change = p2_updated.rating - p2_pre_rating.unwrap_or(p2_updated.rating);
+
+        let p1_msg = ServerMessage::MatchResult {
+            new_rating: p1_updated.rating, rating_change: p1_rating_change, new_rd: p1_updated.rating_deviation,
+            wins: p1_updated.wins, losses: p1_updated.losses,
+        };
+        let p2_msg = ServerMessage::MatchResult {
+            new_rating: p2_updated.rating, rating_change: p2_rating_change, new_rd: p2_updated.rating_deviation,
+            wins: p2_updated.wins, losses: p2_updated.losses,
+        };
+        let _ = send_to(&session.player1, &p1_msg).await;
+        let _ = send_to(&session.player2, &p2_msg).await;
+
+        println!("Match result: {} (rating: {:.0}) vs {} (rating: {:.0})", session.player1.id, p1_updated.rating, session.player2.id, p2_updated.rating);
+
+        let winner_id = if p1_result == GameResult::Win {
+            Some(session.player1.id)
+        } else if p2_result == GameResult::Win {
+            Some(session.player2.id)
+        } else {
+            None
+        };
+        if let Err(e) = db.record_match(
+            session.player1.id, session.player2.id,
+            score1, score2,
+            p1_rating_change, p2_rating_change,
+            winner_id,
+        ).await {
+            println!("Failed to record match history: {}", e);
         }
+
+        // Rating just changed, so the leaderboard table is stale.
+        *leaderboard_version.write().await += 1;
     }
 
-    async fn handle_leave(&self, player_id: PlayerId) {
-        *self.active.write().await = false;
+    metrics.matches_completed.inc();
+    metrics.match_duration_seconds.observe(session.start_time.elapsed().as_secs_f64());
+    metrics.games_active.dec();
+    session.active = false;
+}
+
+/// The actor loop backing one match: a single `tokio::select!` combines the
+/// 1-second countdown tick with incoming `GameCommand`s, so timer expiry,
+/// scoring, and rematch transitions are all serialized through one place.
+/// Unlike the old per-match timer, which was re-spawned on every rematch, a
+/// `GameCommand::Rematch` just resets this same loop's countdown - so there's
+/// never a second ticker racing the first.
+async fn run_game_actor(
+    mut session: GameSession,
+    db: Database,
+    leaderboard_version: Arc<RwLock<u64>>,
+    metrics: Arc<Metrics>,
+    games: Arc<RwLock<HashMap<GameId, GameHandle>>>,
+    player_to_game: Arc<RwLock<HashMap<PlayerId, GameId>>>,
+    mut cmd_rx: mpsc::Receiver<GameCommand>,
+) {
+    let game_span = tracing::info_span!("game", game_id = %session.id);
+    async move { run_game_actor_inner(&mut session, &db, &leaderboard_version, &metrics, &mut cmd_rx).await }
+        .instrument(game_span)
+        .await;
+
+    // The loop above only exits once the match is over and either nobody
+    // asked for a rematch within `POST_MATCH_CLEANUP_GRACE` or a player
+    // left/disconnected for good - either way, nothing will ever send this
+    // actor another command, so drop its entries rather than leaking a
+    // `cmd_tx` (and this task) for the rest of the process's life.
+    games.write().await.remove(&session.id);
+    player_to_game.write().await.remove(&session.player1.id);
+    player_to_game.write().await.remove(&session.player2.id);
+}
+
+/// Body of `run_game_actor`, split out so the whole thing can be wrapped in
+/// one `tracing` span per game via `Instrument` - entering a plain `Span`
+/// guard wouldn't survive the `await` points in here.
+async fn run_game_actor_inner(
+    session: &mut GameSession,
+    db: &Database,
+    leaderboard_version: &Arc<RwLock<u64>>,
+    metrics: &Arc<Metrics>,
+    cmd_rx: &mut mpsc::Receiver<GameCommand>,
+) {
+    let mut ticker = interval(Duration::from_secs(1));
+    let mut elapsed_secs: u64 = 0;
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                if !session.active {
+                    // Between the match ending and a rematch being accepted
+                    // (or the players just leaving) - count how long it's
+                    // been idle, and give up on this actor once nobody's
+                    // coming back for a rematch.
+                    elapsed_secs += 1;
+                    if elapsed_secs >= POST_MATCH_CLEANUP_GRACE.as_secs() {
+                        return;
+                    }
+                    continue;
+                }
 
-        let leave_msg = ServerMessage::OpponentLeft;
-        let leave_str = serde_json::to_string(&leave_msg).unwrap();
+                if let Some(timed_out) = session.timed_out_player(RECONNECT_GRACE) {
+                    end_match(session, db, leaderboard_version, metrics, Some(timed_out)).await;
+                    elapsed_secs = 0;
+                    continue;
+                }
 
-        // Notify the other player
-        if player_id == self.player1.id {
-            let _ = self.player2.tx.send(Message::Text(leave_str));
-        } else {
-            let _ = self.player1.tx.send(Message::Text(leave_str));
+                if session.is_paused() {
+                    // A disconnected player still has time left on their
+                    // grace period - freeze the countdown until they resume
+                    // or it expires.
+                    continue;
+                }
+
+                elapsed_secs += 1;
+                if elapsed_secs >= GAME_DURATION {
+                    end_match(session, db, leaderboard_version, metrics, None).await;
+                    elapsed_secs = 0;
+                    continue;
+                }
+
+                let remaining = GAME_DURATION - elapsed_secs;
+                let msg = ServerMessage::TimeUpdate { seconds_remaining: remaining };
+                let (p1_id, p2_id) = (session.player1.id, session.player2.id);
+                session.send_or_mark_disconnected(p1_id, &msg).await;
+                session.send_or_mark_disconnected(p2_id, &msg).await;
+            }
+            cmd = cmd_rx.recv() => {
+                let Some(cmd) = cmd else { break };
+                match cmd {
+                    GameCommand::Swap { from_player, row1, col1, row2, col2 } => {
+                        session.handle_swap(from_player, row1, col1, row2, col2).await;
+                    }
+                    GameCommand::Score { player_id, new_score } => {
+                        session.update_score(player_id, new_score).await;
+                    }
+                    GameCommand::Garbage { from_player, amount } => {
+                        session.handle_garbage(from_player, amount).await;
+                    }
+                    GameCommand::Special { from_player, row, col } => {
+                        session.handle_special(from_player, row, col).await;
+                    }
+                    GameCommand::Booster { from_player, booster_id } => {
+                        session.handle_booster(from_player, booster_id).await;
+                    }
+                    GameCommand::Emote { from_player, emote_id } => {
+                        session.handle_emote(from_player, emote_id).await;
+                    }
+                    GameCommand::BoardUpdate { from_player, grid, score, energy } => {
+                        session.handle_board_update(from_player, grid, score, energy).await;
+                    }
+                    GameCommand::InputFrame { from_player, frame, inputs } => {
+                        session.handle_input_frame(from_player, frame, inputs).await;
+                    }
+                    GameCommand::StateChecksum { from_player, frame, hash } => {
+                        session.handle_state_checksum(from_player, frame, hash).await;
+                    }
+                    GameCommand::Chat { from_player, from_name, text } => {
+                        session.handle_chat(from_player, from_name, text).await;
+                    }
+                    GameCommand::Spectate { player } => {
+                        session.add_spectator(player);
+                    }
+                    GameCommand::Rematch { player_id } => {
+                        if session.handle_rematch_request(player_id).await {
+                            metrics.games_active.inc();
+                            elapsed_secs = 0;
+                        }
+                    }
+                    GameCommand::Leave { player_id } => {
+                        session.handle_leave(player_id).await;
+                    }
+                    GameCommand::Forfeit { player_id } => {
+                        end_match(session, db, leaderboard_version, metrics, Some(player_id)).await;
+                        elapsed_secs = 0;
+                    }
+                    GameCommand::Disconnect { player_id } => {
+                        session.handle_disconnect(player_id).await;
+                    }
+                    GameCommand::Resume { player_id, new_tx, known_board_version, respond_to } => {
+                        let result = session.handle_resume(player_id, new_tx, known_board_version, elapsed_secs).await;
+                        let _ = respond_to.send(result);
+                    }
+                    GameCommand::GetStatus { respond_to } => {
+                        let _ = respond_to.send(session.active);
+                    }
+                }
+            }
         }
     }
 }
 
+/// A running match, as seen from the rest of the server: its id, both
+/// players' ids (immutable for the match's lifetime, so safe to read
+/// without going through the actor), and the channel used to send it
+/// commands. `GameSession` itself lives only inside the actor task spawned
+/// alongside this handle.
+#[derive(Clone)]
+struct GameHandle {
+    id: GameId,
+    player1_id: PlayerId,
+    player2_id: PlayerId,
+    cmd_tx: mpsc::Sender<GameCommand>,
+}
+
 // Server State
 #[derive(Clone)]
 struct ServerState {
     players: Arc<RwLock<HashMap<PlayerId, Player>>>,
-    games: Arc<RwLock<HashMap<GameId, Arc<GameSession>>>>,
-    matchmaking_queue: Arc<Mutex<Vec<PlayerId>>>,
+    games: Arc<RwLock<HashMap<GameId, GameHandle>>>,
+    // Each entry's `Instant` is when that player joined, used by
+    // `find_compatible_pair` to widen the acceptable rating gap the longer
+    // they've waited.
+    matchmaking_queue: Arc<Mutex<Vec<(PlayerId, Instant)>>>,
     player_to_game: Arc<RwLock<HashMap<PlayerId, GameId>>>,
+    // Opaque per-connection token handed out at auth time so a dropped
+    // client can prove who it was without resending credentials.
+    session_tokens: Arc<RwLock<HashMap<String, PlayerId>>>,
+    // Bumped every time a match result changes the ratings table, so
+    // `fetch_leaderboard` can tell a client its cached copy is still current
+    // without re-querying and re-sorting the database.
+    leaderboard_version: Arc<RwLock<u64>>,
+    // Authenticated username for each connected player, populated at Login;
+    // looked up to label in-match chat lines and `list_matches` entries.
+    usernames: Arc<RwLock<HashMap<PlayerId, String>>>,
     db: Database,
+    // Cluster membership and the HTTP client used to reach peers. A game_id
+    // never appears in `games` unless this node owns it per
+    // `cluster.owning_node`; a locally-connected player whose opponent is on
+    // another node gets a proxy `Player` (see `cluster::spawn_remote_player_proxy`)
+    // standing in for them in `games`/`player_to_game` exactly like a local one.
+    cluster: Arc<ClusterConfig>,
+    cluster_client: ClusterClient,
+    metrics: Arc<Metrics>,
 }
 
 impl ServerState {
@@ -285,176 +803,538 @@ impl ServerState {
             games: Arc::new(RwLock::new(HashMap::new())),
             matchmaking_queue: Arc::new(Mutex::new(Vec::new())),
             player_to_game: Arc::new(RwLock::new(HashMap::new())),
+            session_tokens: Arc::new(RwLock::new(HashMap::new())),
+            leaderboard_version: Arc::new(RwLock::new(0)),
+            usernames: Arc::new(RwLock::new(HashMap::new())),
             db,
+            cluster: Arc::new(ClusterConfig::load()),
+            cluster_client: ClusterClient::new(),
+            metrics: Arc::new(Metrics::new()),
+        }
+    }
+
+    /// Answers `FetchLeaderboard`: replies `LeaderboardUnchanged` if the
+    /// caller's `since_version` already matches ours, otherwise re-queries
+    /// the database and replies with the fresh table and version.
+    async fn fetch_leaderboard(&self, player_id: PlayerId, since_version: u64) {
+        let current_version = *self.leaderboard_version.read().await;
+        let msg = if since_version == current_version {
+            ServerMessage::LeaderboardUnchanged
+        } else {
+            match self.db.get_leaderboard().await {
+                Ok(players) => ServerMessage::LeaderboardData { version: current_version, players },
+                Err(e) => ServerMessage::Error { message: format!("Failed to fetch leaderboard: {}", e) },
+            }
+        };
+
+        if let Some(player) = self.players.read().await.get(&player_id) {
+            let _ = send_to(player, &msg).await;
+        }
+    }
+
+    /// Answers `RequestHistory`: a page of `player_id`'s own completed
+    /// matches, newest first, reordered from the stored player1/player2
+    /// fields into the caller's own perspective.
+    async fn request_history(&self, player_id: PlayerId, limit: u32, before: Option<i64>) {
+        let msg = match self.db.get_match_history(player_id, limit, before).await {
+            Ok((entries, next_cursor)) => {
+                let usernames = self.usernames.read().await;
+                let matches = entries
+                    .into_iter()
+                    .map(|entry| {
+                        let is_p1 = entry.player1_id == player_id;
+                        let opponent_id = if is_p1 { entry.player2_id } else { entry.player1_id };
+                        let (player_score, opponent_score) = if is_p1 {
+                            (entry.player1_score, entry.player2_score)
+                        } else {
+                            (entry.player2_score, entry.player1_score)
+                        };
+                        let rating_change = if is_p1 { entry.player1_rating_change } else { entry.player2_rating_change };
+                        let result = match entry.winner_id {
+                            Some(winner) if winner == player_id => GameResult::Win,
+                            Some(_) => GameResult::Loss,
+                            None => GameResult::Tie,
+                        };
+                        MatchRecord {
+                            match_id: entry.id,
+                            opponent_name: usernames.get(&opponent_id).cloned().unwrap_or_default(),
+                            player_score,
+                            opponent_score,
+                            result,
+                            rating_change,
+                            played_at: entry.played_at,
+                        }
+                    })
+                    .collect();
+                ServerMessage::MatchHistory { matches, next_cursor }
+            }
+            Err(e) => ServerMessage::Error { message: format!("Failed to fetch match history: {}", e) },
+        };
+
+        if let Some(player) = self.players.read().await.get(&player_id) {
+            let _ = send_to(player, &msg).await;
+        }
+    }
+
+    /// Answers `RequestHeadToHead`: up to `HEAD_TO_HEAD_LIMIT` of
+    /// `player_id`'s most recent matches against `opponent_id`, newest
+    /// first, plus their overall record against that one opponent.
+    async fn request_head_to_head(&self, player_id: PlayerId, opponent_id: PlayerId) {
+        let msg = match self.db.get_head_to_head(player_id, opponent_id, HEAD_TO_HEAD_LIMIT).await {
+            Ok((entries, wins, losses)) => {
+                let usernames = self.usernames.read().await;
+                let matches = entries
+                    .into_iter()
+                    .map(|entry| {
+                        let is_p1 = entry.player1_id == player_id;
+                        let opponent = if is_p1 { entry.player2_id } else { entry.player1_id };
+                        let (player_score, opponent_score) = if is_p1 {
+                            (entry.player1_score, entry.player2_score)
+                        } else {
+                            (entry.player2_score, entry.player1_score)
+                        };
+                        let rating_change = if is_p1 { entry.player1_rating_change } else { entry.player2_rating_change };
+                        let result = match entry.winner_id {
+                            Some(winner) if winner == player_id => GameResult::Win,
+                            Some(_) => GameResult::Loss,
+                            None => GameResult::Tie,
+                        };
+                        MatchRecord {
+                            match_id: entry.id,
+                            opponent_name: usernames.get(&opponent).cloned().unwrap_or_default(),
+                            player_score,
+                            opponent_score,
+                            result,
+                            rating_change,
+                            played_at: entry.played_at,
+                        }
+                    })
+                    .collect();
+                ServerMessage::HeadToHead { matches, wins, losses }
+            }
+            Err(e) => ServerMessage::Error { message: format!("Failed to fetch head-to-head history: {}", e) },
+        };
+
+        if let Some(player) = self.players.read().await.get(&player_id) {
+            let _ = send_to(player, &msg).await;
+        }
+    }
+
+    /// Answers `ListMatches`: every still-active game, with both players'
+    /// usernames resolved the same way `create_match` resolves an opponent's.
+    /// "Active" is asked of each game's actor via `GameCommand::GetStatus`,
+    /// since that's the one place the flag is now owned.
+    async fn list_matches(&self, player_id: PlayerId) {
+        let handles: Vec<GameHandle> = self.games.read().await.values().cloned().collect();
+        let usernames = self.usernames.read().await;
+
+        let mut matches = Vec::new();
+        for handle in handles {
+            let (respond_to, response) = oneshot::channel();
+            if handle.cmd_tx.send(GameCommand::GetStatus { respond_to }).await.is_err() {
+                continue;
+            }
+            if let Ok(true) = response.await {
+                let name1 = usernames.get(&handle.player1_id).cloned().unwrap_or_default();
+                let name2 = usernames.get(&handle.player2_id).cloned().unwrap_or_default();
+                matches.push((handle.id, name1, name2));
+            }
+        }
+        drop(usernames);
+
+        let msg = ServerMessage::MatchList { matches };
+        if let Some(player) = self.players.read().await.get(&player_id) {
+            let _ = send_to(player, &msg).await;
         }
     }
 
     async fn add_player(&self, player: Player) {
         let player_id = player.id;
         self.players.write().await.insert(player_id, player.clone());
+        self.metrics.players_online.inc();
 
         // Send connection confirmation
         let msg = ServerMessage::Connected { player_id };
-        let _ = player.tx.send(Message::Text(serde_json::to_string(&msg).unwrap()));
+        let _ = send_to(&player, &msg).await;
     }
 
     async fn remove_player(&self, player_id: PlayerId) {
-        self.players.write().await.remove(&player_id);
+        if self.players.write().await.remove(&player_id).is_some() {
+            self.metrics.players_online.dec();
+        }
 
         // Remove from queue if present
         let mut queue = self.matchmaking_queue.lock().await;
-        queue.retain(|&id| id != player_id);
+        queue.retain(|(id, _)| *id != player_id);
+        self.metrics.queue_length.set(queue.len() as i64);
         drop(queue);
 
-        // Handle game disconnect
-        if let Some(game_id) = self.player_to_game.read().await.get(&player_id) {
-            if let Some(game) = self.games.read().await.get(game_id) {
-                game.handle_disconnect(player_id).await;
+        // Handle game disconnect. Keep the `player_to_game` entry so a
+        // `Resume` can still find this game after the socket drops.
+        if let Some(game_id) = self.player_to_game.read().await.get(&player_id).copied() {
+            if let Some(handle) = self.games.read().await.get(&game_id) {
+                let _ = handle.cmd_tx.send(GameCommand::Disconnect { player_id }).await;
+            } else {
+                // The match is owned by a peer node - let it know this side
+                // dropped, the closest cluster-aware equivalent of the local
+                // `GameCommand::Disconnect` path above.
+                let owner = self.cluster.owning_node(game_id);
+                if let Some(peer_addr) = self.cluster.peers.get(&owner) {
+                    let _ = self.cluster_client.forward_command(peer_addr, player_id, ClientMessage::LeaveGame).await;
+                }
             }
-            self.player_to_game.write().await.remove(&player_id);
+        }
+    }
+
+    /// Reattaches a reconnecting client to its in-progress game: asks the
+    /// game's actor to swap in the new sender and hand back enough state to
+    /// rehydrate the board, then reinserts the (tx-swapped) `Player` into
+    /// `self.players` - needed because `remove_player` dropped it wholesale
+    /// on disconnect.
+    async fn resume_player(&self, session_token: &str, tx: Tx, known_board_version: u64) -> Option<ResumeResult> {
+        let player_id = *self.session_tokens.read().await.get(session_token)?;
+        let game_id = *self.player_to_game.read().await.get(&player_id)?;
+        let handle = self.games.read().await.get(&game_id)?.clone();
+
+        let (respond_to, response) = oneshot::channel();
+        handle.cmd_tx.send(GameCommand::Resume { player_id, new_tx: tx, known_board_version, respond_to }).await.ok()?;
+        let result = response.await.ok().flatten()?;
+
+        self.players.write().await.insert(player_id, result.player.clone());
+        self.metrics.players_online.inc();
+
+        Some(result)
+    }
+
+    /// Registers `player_id` as a spectator of `game_id`, if it's still
+    /// registered. Idempotent so a client can safely resend it.
+    async fn spectate_game(&self, player_id: PlayerId, game_id: GameId) {
+        let player = match self.players.read().await.get(&player_id) {
+            Some(player) => player.clone(),
+            None => return,
+        };
+        if let Some(handle) = self.games.read().await.get(&game_id) {
+            let _ = handle.cmd_tx.send(GameCommand::Spectate { player }).await;
         }
     }
 
     async fn join_queue(&self, player_id: PlayerId) {
         let mut queue = self.matchmaking_queue.lock().await;
 
-        if !queue.contains(&player_id) {
-            queue.push(player_id);
+        if !queue.iter().any(|(id, _)| *id == player_id) {
+            let joined_at = Instant::now();
+            queue.push((player_id, joined_at));
+            self.metrics.queue_length.set(queue.len() as i64);
 
             let position = queue.len();
             if let Some(player) = self.players.read().await.get(&player_id) {
                 let msg = ServerMessage::Queued { position };
-                let _ = player.tx.send(Message::Text(serde_json::to_string(&msg).unwrap()));
+                let _ = send_to(player, &msg).await;
             }
+            drop(queue);
 
-            // Try to match players
-            if queue.len() >= 2 {
-                let p1_id = queue.remove(0);
-                let p2_id = queue.remove(0);
-                drop(queue);
-
+            // Try to find a rating-compatible pair among everyone waiting.
+            if let Some((p1_id, p2_id)) = self.find_compatible_pair().await {
                 self.create_match(p1_id, p2_id).await;
+                return;
+            }
+
+            // No local opponent waiting - ask each peer node in turn whether
+            // it has a compatible player queued, and pair with the first one
+            // that does. Our own rating/RD goes along with the request so
+            // the answering node can apply the same confidence-interval
+            // check `find_compatible_pair` does locally, rather than
+            // handing back its first-in-line player regardless of rating.
+            let me = self.db.get_user_by_id(player_id).await.ok();
+            let (my_rating, my_rd) = me
+                .map(|u| (u.rating, u.rating_deviation))
+                .unwrap_or((db::DEFAULT_RATING, db::DEFAULT_RATING_DEVIATION));
+
+            for peer_addr in self.cluster.peer_addrs() {
+                let waited_secs = joined_at.elapsed().as_secs_f64();
+                match self.cluster_client.request_match(&peer_addr, my_rating, my_rd, waited_secs).await {
+                    Ok(Some((remote_id, remote_name))) => {
+                        // Someone else may have matched us locally while this
+                        // round-trip was in flight; don't double-match.
+                        if self.player_to_game.read().await.contains_key(&player_id) {
+                            return;
+                        }
+                        let mut queue = self.matchmaking_queue.lock().await;
+                        queue.retain(|(id, _)| *id != player_id);
+                        self.metrics.queue_length.set(queue.len() as i64);
+                        drop(queue);
+                        self.create_cross_node_match(player_id, remote_id, remote_name, peer_addr).await;
+                        return;
+                    }
+                    Ok(None) => continue,
+                    Err(e) => {
+                        println!("Cross-node match request to {} failed: {}", peer_addr, e);
+                        continue;
+                    }
+                }
             }
         }
     }
 
+    /// Scans the local queue for the first two waiting players whose
+    /// ratings are close enough per `db::confidence_intervals_overlap`,
+    /// using however long the longer-waiting of the two has been queued to
+    /// decide how wide a gap to tolerate. Removes and returns that pair if
+    /// one is found, leaving everyone else (and a non-matching queue)
+    /// untouched.
+    async fn find_compatible_pair(&self) -> Option<(PlayerId, PlayerId)> {
+        let snapshot = self.matchmaking_queue.lock().await.clone();
+        let now = Instant::now();
+
+        for i in 0..snapshot.len() {
+            for j in (i + 1)..snapshot.len() {
+                let (id_a, joined_a) = snapshot[i];
+                let (id_b, joined_b) = snapshot[j];
+
+                let (Ok(a), Ok(b)) = (self.db.get_user_by_id(id_a).await, self.db.get_user_by_id(id_b).await) else {
+                    continue;
+                };
+                let waited_secs = now.duration_since(joined_a.min(joined_b)).as_secs_f64();
+
+                if db::confidence_intervals_overlap(a.rating, a.rating_deviation, b.rating, b.rating_deviation, waited_secs) {
+                    let mut queue = self.matchmaking_queue.lock().await;
+                    // Someone else may have matched one of these two (locally
+                    // or cross-node) while we were awaiting the rating
+                    // lookups above; only claim the pair if both are still
+                    // actually waiting, so two concurrent `join_queue` calls
+                    // can't both snapshot the same pair and each spin up
+                    // their own match for it.
+                    let both_still_queued = queue.iter().filter(|(id, _)| *id == id_a || *id == id_b).count() == 2;
+                    if !both_still_queued {
+                        continue;
+                    }
+                    queue.retain(|(id, _)| *id != id_a && *id != id_b);
+                    self.metrics.queue_length.set(queue.len() as i64);
+                    return Some((id_a, id_b));
+                }
+            }
+        }
+
+        None
+    }
+
     async fn create_match(&self, p1_id: PlayerId, p2_id: PlayerId) {
-        let players = self.players.read().await;
+        let (p1, p2) = {
+            let players = self.players.read().await;
+            match (players.get(&p1_id), players.get(&p2_id)) {
+                (Some(p1), Some(p2)) => (p1.clone(), p2.clone()),
+                _ => return,
+            }
+        };
 
-        if let (Some(p1), Some(p2)) = (players.get(&p1_id), players.get(&p2_id)) {
-            let game = Arc::new(GameSession::new(p1.clone(), p2.clone()));
-            let game_id = game.id;
+        let usernames = self.usernames.read().await;
+        let p1_name = usernames.get(&p1_id).cloned().unwrap_or_else(|| "Player".to_string());
+        let p2_name = usernames.get(&p2_id).cloned().unwrap_or_else(|| "Player".to_string());
+        drop(usernames);
 
-            // Notify both players of the match
-            let match_msg_p1 = ServerMessage::MatchFound {
-                game_id,
-                opponent_id: p2_id
-            };
-            let match_msg_p2 = ServerMessage::MatchFound {
-                game_id,
-                opponent_id: p1_id
-            };
+        self.create_match_between(p1, p1_name, p2, p2_name).await;
+    }
 
-            let _ = p1.tx.send(Message::Text(serde_json::to_string(&match_msg_p1).unwrap()));
-            let _ = p2.tx.send(Message::Text(serde_json::to_string(&match_msg_p2).unwrap()));
+    /// Pairs a player connected to this node with one whose real connection
+    /// lives on `peer_addr`. This node always ends up owning the resulting
+    /// match (it generates a `game_id` it owns, see
+    /// `ClusterConfig::owning_node`), and represents the remote player with
+    /// a proxy `Player` that relays everything the actor sends it back to
+    /// `peer_addr` over HTTP.
+    async fn create_cross_node_match(&self, local_id: PlayerId, remote_id: PlayerId, remote_name: String, peer_addr: String) {
+        let local_player = match self.players.read().await.get(&local_id) {
+            Some(p) => p.clone(),
+            None => return,
+        };
+        let local_name = self.usernames.read().await.get(&local_id).cloned().unwrap_or_else(|| "Player".to_string());
+
+        self.usernames.write().await.insert(remote_id, remote_name.clone());
+        let remote_player = cluster::spawn_remote_player_proxy(self.cluster_client.clone(), peer_addr, remote_id);
+
+        self.create_match_between(local_player, local_name, remote_player, remote_name).await;
+    }
 
-            // Register game
-            self.games.write().await.insert(game_id, game.clone());
-            self.player_to_game.write().await.insert(p1_id, game_id);
-            self.player_to_game.write().await.insert(p2_id, game_id);
+    /// Shared tail of `create_match`/`create_cross_node_match`: picks a
+    /// `game_id` this node owns, notifies both `Player`s (real or proxy -
+    /// they're indistinguishable from here on), and spawns the actor.
+    async fn create_match_between(&self, p1: Player, p1_name: String, p2: Player, p2_name: String) {
+        let p1_id = p1.id;
+        let p2_id = p2.id;
+
+        // Rejection-sample a fresh id until this node is its owner per the
+        // cluster's deterministic rule, so no separate ownership handshake
+        // is needed - every node that later learns this game_id can compute
+        // the same answer on its own.
+        let mut game_id = Uuid::new_v4();
+        while self.cluster.owning_node(game_id) != self.cluster.local_node_id {
+            game_id = Uuid::new_v4();
+        }
 
-            // Start the game
-            game.start(self.db.clone()).await;
+        let mut session = GameSession::new(p1.clone(), p2.clone());
+        session.id = game_id;
+
+        // Best-effort win estimate for each side, from whatever rating each
+        // player currently has; missing either one just reports a coin-flip
+        // rather than failing the match.
+        let (p1_rating, p2_rating) = (
+            self.db.get_user_by_id(p1_id).await.map(|u| u.rating).unwrap_or(db::DEFAULT_RATING),
+            self.db.get_user_by_id(p2_id).await.map(|u| u.rating).unwrap_or(db::DEFAULT_RATING),
+        );
+        let p1_win_probability = db::expected_score(p1_rating, p2_rating);
+        let p2_win_probability = 1.0 - p1_win_probability;
+
+        // Notify both players of the match, then that it's started - both
+        // sent synchronously here so a client sees that ordering
+        // deterministically, before the match's actor task (spawned below)
+        // starts driving it.
+        let match_msg_p1 = ServerMessage::MatchFound { game_id, opponent_id: p2_id, opponent_name: p2_name, win_probability: p1_win_probability };
+        let match_msg_p2 = ServerMessage::MatchFound { game_id, opponent_id: p1_id, opponent_name: p1_name, win_probability: p2_win_probability };
+        let _ = send_to(&p1, &match_msg_p1).await;
+        let _ = send_to(&p2, &match_msg_p2).await;
+
+        let start_msg = ServerMessage::GameStarted { game_id, seed: session.seed };
+        let _ = send_to(&p1, &start_msg).await;
+        let _ = send_to(&p2, &start_msg).await;
+
+        let (cmd_tx, cmd_rx) = mpsc::channel(64);
+        let handle = GameHandle { id: game_id, player1_id: p1_id, player2_id: p2_id, cmd_tx };
+
+        self.games.write().await.insert(game_id, handle);
+        self.player_to_game.write().await.insert(p1_id, game_id);
+        self.player_to_game.write().await.insert(p2_id, game_id);
+        self.metrics.games_active.inc();
+
+        tokio::spawn(run_game_actor(
+            session,
+            self.db.clone(),
+            self.leaderboard_version.clone(),
+            self.metrics.clone(),
+            self.games.clone(),
+            self.player_to_game.clone(),
+            cmd_rx,
+        ));
+    }
+
+    /// Looks up `player_id`'s current match and forwards `cmd` to its actor,
+    /// if any. Centralizes the `player_to_game` -> `games` double lookup
+    /// every in-match command needs.
+    async fn send_game_command(&self, player_id: PlayerId, cmd: GameCommand) {
+        if let Some(game_id) = self.player_to_game.read().await.get(&player_id) {
+            if let Some(handle) = self.games.read().await.get(game_id) {
+                let _ = handle.cmd_tx.send(cmd).await;
+            }
         }
     }
 
+    /// True if `msg` is scoped to whatever match `player_id` is currently
+    /// in, rather than to matchmaking/lobby state - the set this node must
+    /// forward to a peer instead of handling locally when that match's
+    /// actor lives there.
+    fn is_game_scoped(msg: &ClientMessage) -> bool {
+        matches!(
+            msg,
+            ClientMessage::SwapGems { .. }
+                | ClientMessage::ScoreUpdate { .. }
+                | ClientMessage::SendGarbage { .. }
+                | ClientMessage::ActivateSpecial { .. }
+                | ClientMessage::ActivateBooster { .. }
+                | ClientMessage::SendEmote { .. }
+                | ClientMessage::BoardUpdate { .. }
+                | ClientMessage::InputFrame { .. }
+                | ClientMessage::StateChecksum { .. }
+                | ClientMessage::Chat { .. }
+                | ClientMessage::RequestRematch
+                | ClientMessage::LeaveGame
+                | ClientMessage::Forfeit
+        )
+    }
+
     async fn handle_client_message(&self, player_id: PlayerId, msg: ClientMessage) {
+        // If this player's match is owned by a peer node (its game_id isn't
+        // in our local `games`), forward the raw message there instead of
+        // dispatching it ourselves.
+        if Self::is_game_scoped(&msg) {
+            if let Some(game_id) = self.player_to_game.read().await.get(&player_id).copied() {
+                if !self.games.read().await.contains_key(&game_id) {
+                    let owner = self.cluster.owning_node(game_id);
+                    if let Some(peer_addr) = self.cluster.peers.get(&owner) {
+                        if let Err(e) = self.cluster_client.forward_command(peer_addr, player_id, msg).await {
+                            println!("Failed to forward command to {}: {}", peer_addr, e);
+                        }
+                    }
+                    return;
+                }
+            }
+        }
+
         match msg {
-            ClientMessage::Login { .. } => {
-                // Login is handled in handle_connection, ignore here
-                // If we receive Login after authentication, just ignore it
+            ClientMessage::Hello { .. }
+            | ClientMessage::RequestServerStatus
+            | ClientMessage::Login { .. }
+            | ClientMessage::Register { .. }
+            | ClientMessage::Resume { .. } => {
+                // All five are handshake-only messages handled in
+                // handle_connection; the recv loop filters them out before
+                // they reach here.
             }
             ClientMessage::JoinQueue => {
                 self.join_queue(player_id).await;
             }
             ClientMessage::SwapGems { row1, col1, row2, col2 } => {
-                if let Some(game_id) = self.player_to_game.read().await.get(&player_id) {
-                    if let Some(game) = self.games.read().await.get(game_id) {
-                        game.handle_swap(player_id, row1, col1, row2, col2).await;
-                    }
-                }
+                self.send_game_command(player_id, GameCommand::Swap { from_player: player_id, row1, col1, row2, col2 }).await;
             }
             ClientMessage::ScoreUpdate { score } => {
-                if let Some(game_id) = self.player_to_game.read().await.get(&player_id) {
-                    if let Some(game) = self.games.read().await.get(game_id) {
-                        game.update_score(player_id, score).await;
-                    }
-                }
+                self.send_game_command(player_id, GameCommand::Score { player_id, new_score: score }).await;
             }
             ClientMessage::SendGarbage { amount } => {
-                if let Some(game_id) = self.player_to_game.read().await.get(&player_id) {
-                    if let Some(game) = self.games.read().await.get(game_id) {
-                        // Send garbage to opponent
-                        let opponent_tx = if player_id == game.player1.id {
-                            &game.player2.tx
-                        } else {
-                            &game.player1.tx
-                        };
-
-                        let garbage_msg = ServerMessage::ReceiveGarbage { amount };
-                        let _ = opponent_tx.send(Message::Text(
-                            serde_json::to_string(&garbage_msg).unwrap()
-                        ));
-                    }
-                }
+                self.send_game_command(player_id, GameCommand::Garbage { from_player: player_id, amount }).await;
             }
             ClientMessage::ActivateSpecial { row, col } => {
-                if let Some(game_id) = self.player_to_game.read().await.get(&player_id) {
-                    if let Some(game) = self.games.read().await.get(game_id) {
-                        // Notify opponent about special activation
-                        let opponent_tx = if player_id == game.player1.id {
-                            &game.player2.tx
-                        } else {
-                            &game.player1.tx
-                        };
-
-                        let special_msg = ServerMessage::OpponentActivatedSpecial { row, col };
-                        let _ = opponent_tx.send(Message::Text(
-                            serde_json::to_string(&special_msg).unwrap()
-                        ));
-                    }
-                }
+                self.send_game_command(player_id, GameCommand::Special { from_player: player_id, row, col }).await;
             }
             ClientMessage::ActivateBooster { booster_id } => {
-                if let Some(game_id) = self.player_to_game.read().await.get(&player_id) {
-                    if let Some(game) = self.games.read().await.get(game_id) {
-                        // Notify opponent about booster activation
-                        let opponent_tx = if player_id == game.player1.id {
-                            &game.player2.tx
-                        } else {
-                            &game.player1.tx
-                        };
-
-                        let booster_msg = ServerMessage::OpponentActivatedBooster { booster_id };
-                        let _ = opponent_tx.send(Message::Text(
-                            serde_json::to_string(&booster_msg).unwrap()
-                        ));
-                    }
-                }
+                self.send_game_command(player_id, GameCommand::Booster { from_player: player_id, booster_id }).await;
+            }
+            ClientMessage::SendEmote { emote_id } => {
+                self.send_game_command(player_id, GameCommand::Emote { from_player: player_id, emote_id }).await;
+            }
+            ClientMessage::BoardUpdate { grid, score, energy } => {
+                self.send_game_command(player_id, GameCommand::BoardUpdate { from_player: player_id, grid, score, energy }).await;
+            }
+            ClientMessage::SpectateGame { game_id } => {
+                self.spectate_game(player_id, game_id).await;
+            }
+            ClientMessage::FetchLeaderboard { since_version } => {
+                self.fetch_leaderboard(player_id, since_version).await;
+            }
+            ClientMessage::ListMatches => {
+                self.list_matches(player_id).await;
+            }
+            ClientMessage::RequestHistory { limit, before } => {
+                self.request_history(player_id, limit, before).await;
+            }
+            ClientMessage::RequestHeadToHead { opponent_id } => {
+                self.request_head_to_head(player_id, opponent_id).await;
+            }
+            ClientMessage::InputFrame { frame, inputs } => {
+                self.send_game_command(player_id, GameCommand::InputFrame { from_player: player_id, frame, inputs }).await;
+            }
+            ClientMessage::Chat { text } => {
+                let from_name = self.usernames.read().await.get(&player_id).cloned().unwrap_or_else(|| "Player".to_string());
+                self.send_game_command(player_id, GameCommand::Chat { from_player: player_id, from_name, text }).await;
+            }
+            ClientMessage::StateChecksum { frame, hash } => {
+                self.send_game_command(player_id, GameCommand::StateChecksum { from_player: player_id, frame, hash }).await;
             }
             ClientMessage::RequestRematch => {
-                if let Some(game_id) = self.player_to_game.read().await.get(&player_id) {
-                    if let Some(game) = self.games.read().await.get(game_id) {
-                        game.handle_rematch_request(player_id, self.db.clone()).await;
-                    }
-                }
+                self.send_game_command(player_id, GameCommand::Rematch { player_id }).await;
             }
             ClientMessage::LeaveGame => {
-                if let Some(game_id) = self.player_to_game.read().await.get(&player_id) {
-                    if let Some(game) = self.games.read().await.get(game_id) {
-                        game.handle_leave(player_id).await;
-                    }
-                }
+                self.send_game_command(player_id, GameCommand::Leave { player_id }).await;
+                self.remove_player(player_id).await;
+            }
+            ClientMessage::Forfeit => {
+                self.send_game_command(player_id, GameCommand::Forfeit { player_id }).await;
                 self.remove_player(player_id).await;
             }
         }
@@ -468,51 +1348,248 @@ async fn handle_connection(
     let (mut ws_sender, mut ws_receiver) = ws_stream.split();
     let (tx, mut rx) = mpsc::unbounded_channel();
 
-    // Wait for Login message (authentication handshake)
-    let player_id = loop {
+    // Protocol-version gate: every connection must say Hello before anything
+    // else, so a client built against an incompatible match3_protocol is
+    // rejected up front instead of desyncing matchmaking or match state.
+    loop {
+        match ws_receiver.next().await {
+            Some(Ok(Message::Text(text))) => {
+                match serde_json::from_str::<ClientMessage>(&text) {
+                    Ok(ClientMessage::Hello { protocol_version, client_build }) => {
+                        let accepted = protocol_version >= MIN_SUPPORTED_PROTOCOL_VERSION;
+                        let ack_msg = ServerMessage::HelloAck {
+                            accepted,
+                            server_version: PROTOCOL_VERSION,
+                            min_supported: MIN_SUPPORTED_PROTOCOL_VERSION,
+                        };
+                        let ack_json = serde_json::to_string(&ack_msg).unwrap();
+                        if ws_sender.send(Message::Text(ack_json)).await.is_err() {
+                            println!("Failed to send HelloAck");
+                            return;
+                        }
+                        if !accepted {
+                            println!(
+                                "Rejected client build {} (protocol v{}, need >= v{})",
+                                client_build, protocol_version, MIN_SUPPORTED_PROTOCOL_VERSION
+                            );
+                            return;
+                        }
+                        println!("Client build {} accepted (protocol v{})", client_build, protocol_version);
+                        break;
+                    }
+                    _ => {
+                        println!("Client sent a message before Hello - dropping connection");
+                        return;
+                    }
+                }
+            }
+            Some(Ok(Message::Close(_))) | None => {
+                println!("Client disconnected before Hello");
+                return;
+            }
+            _ => {
+                // Ignore other message types
+            }
+        }
+    }
+
+    // Wait for a Login (fresh session) or Resume (rejoining an in-progress
+    // game after a dropped socket) message.
+    let (player_id, resumed) = loop {
         match ws_receiver.next().await {
             Some(Ok(Message::Text(text))) => {
                 match serde_json::from_str::<ClientMessage>(&text) {
-                    Ok(ClientMessage::Login { username }) => {
-                        // Authenticate user with database
-                        match state.db.get_or_create_user(&username).await {
+                    Ok(ClientMessage::RequestServerStatus) => {
+                        let players_online = state.players.read().await.len();
+                        let queue_size = state.matchmaking_queue.lock().await.len();
+                        let status_msg = ServerMessage::ServerStatusReport { players_online, queue_size };
+                        let status_json = serde_json::to_string(&status_msg).unwrap();
+                        let _ = ws_sender.send(Message::Text(status_json)).await;
+                        println!("Sent server status: {} online, {} queued", players_online, queue_size);
+                        return;
+                    }
+                    Ok(ClientMessage::Login { username, password }) => {
+                        // Look up the account and verify the password before
+                        // minting a player_id - unknown usernames and wrong
+                        // passwords get distinct rejection reasons so the
+                        // client can tell "no such account" from "try again".
+                        let credentials = match state.db.get_user_by_username(&username).await {
+                            Ok(c) => c,
+                            Err(e) => {
+                                let reject_msg = ServerMessage::AuthRejected {
+                                    reason: format!("Database error: {}", e),
+                                };
+                                let reject_json = serde_json::to_string(&reject_msg).unwrap();
+                                let _ = ws_sender.send(Message::Text(reject_json)).await;
+                                println!("Authentication failed: {}", e);
+                                return;
+                            }
+                        };
+                        let Some(credentials) = credentials else {
+                            let reject_msg = ServerMessage::AuthRejected {
+                                reason: "No account with that username".to_string(),
+                            };
+                            let reject_json = serde_json::to_string(&reject_msg).unwrap();
+                            let _ = ws_sender.send(Message::Text(reject_json)).await;
+                            println!("Login rejected: unknown username {}", username);
+                            return;
+                        };
+
+                        let stored_hash = credentials.password_hash.clone();
+                        let password_ok = tokio::task::spawn_blocking(move || verify_password(&password, &stored_hash))
+                            .await
+                            .unwrap_or(false);
+                        if !password_ok {
+                            let reject_msg = ServerMessage::AuthRejected {
+                                reason: "Incorrect password".to_string(),
+                            };
+                            let reject_json = serde_json::to_string(&reject_msg).unwrap();
+                            let _ = ws_sender.send(Message::Text(reject_json)).await;
+                            println!("Login rejected: wrong password for {}", username);
+                            return;
+                        }
+
+                        let mut user = credentials.user;
+                        // Re-inflate RD for time spent away before reporting
+                        // it back, so a returning player sees their actual
+                        // current uncertainty rather than a stale number.
+                        if state.db.apply_inactivity_decay(user.id).await.is_ok() {
+                            if let Ok(refreshed) = state.db.get_user_by_id(user.id).await {
+                                user = refreshed;
+                            }
+                        }
+                        let session_token = Uuid::new_v4().to_string();
+                        state.session_tokens.write().await.insert(session_token.clone(), user.id);
+
+                        let auth_msg = ServerMessage::AuthAccepted {
+                            player_id: user.id,
+                            username: user.username.clone(),
+                            rating: user.rating,
+                            rating_deviation: user.rating_deviation,
+                            wins: user.wins,
+                            losses: user.losses,
+                            session_token,
+                        };
+                        let auth_json = serde_json::to_string(&auth_msg).unwrap();
+                        if ws_sender.send(Message::Text(auth_json)).await.is_err() {
+                            println!("Failed to send auth accepted");
+                            return;
+                        }
+                        state.usernames.write().await.insert(user.id, user.username.clone());
+                        println!("User authenticated: {} ({})", user.username, user.id);
+                        break (user.id, false);
+                    }
+                    Ok(ClientMessage::Register { username, password }) => {
+                        let hash = match tokio::task::spawn_blocking(move || hash_password(&password)).await {
+                            Ok(Ok(hash)) => hash,
+                            _ => {
+                                let reject_msg = ServerMessage::AuthRejected {
+                                    reason: "Failed to hash password".to_string(),
+                                };
+                                let reject_json = serde_json::to_string(&reject_msg).unwrap();
+                                let _ = ws_sender.send(Message::Text(reject_json)).await;
+                                return;
+                            }
+                        };
+
+                        match state.db.create_user(&username, &hash).await {
                             Ok(user) => {
-                                // Send authentication success
+                                let session_token = Uuid::new_v4().to_string();
+                                state.session_tokens.write().await.insert(session_token.clone(), user.id);
+
                                 let auth_msg = ServerMessage::AuthAccepted {
                                     player_id: user.id,
                                     username: user.username.clone(),
-                                    elo: user.elo,
+                                    rating: user.rating,
+                                    rating_deviation: user.rating_deviation,
                                     wins: user.wins,
                                     losses: user.losses,
+                                    session_token,
                                 };
                                 let auth_json = serde_json::to_string(&auth_msg).unwrap();
                                 if ws_sender.send(Message::Text(auth_json)).await.is_err() {
                                     println!("Failed to send auth accepted");
                                     return;
                                 }
-                                println!("User authenticated: {} ({})", user.username, user.id);
-                                break user.id;
+                                state.usernames.write().await.insert(user.id, user.username.clone());
+                                println!("User registered: {} ({})", user.username, user.id);
+                                break (user.id, false);
                             }
-                            Err(e) => {
-                                // Database error - send rejection
+                            Err(AuthDbError::UsernameTaken) => {
+                                let reject_msg = ServerMessage::AuthRejected {
+                                    reason: "Username already taken".to_string(),
+                                };
+                                let reject_json = serde_json::to_string(&reject_msg).unwrap();
+                                let _ = ws_sender.send(Message::Text(reject_json)).await;
+                                println!("Registration rejected: {} already taken", username);
+                                return;
+                            }
+                            Err(AuthDbError::Sqlx(e)) => {
                                 let reject_msg = ServerMessage::AuthRejected {
                                     reason: format!("Database error: {}", e),
                                 };
                                 let reject_json = serde_json::to_string(&reject_msg).unwrap();
                                 let _ = ws_sender.send(Message::Text(reject_json)).await;
-                                println!("Authentication failed: {}", e);
+                                println!("Registration failed: {}", e);
+                                return;
+                            }
+                        }
+                    }
+                    Ok(ClientMessage::Resume { session_token, known_board_version }) => {
+                        match state.resume_player(&session_token, tx.clone(), known_board_version).await {
+                            Some(result) => {
+                                let resume_msg = ServerMessage::ResumeAccepted {
+                                    game_id: result.game_id,
+                                    seconds_remaining: result.seconds_remaining,
+                                    player_score: result.player_score,
+                                    opponent_score: result.opponent_score,
+                                    pending_garbage: result.pending_garbage,
+                                    board_version: result.board_version,
+                                };
+                                let resume_json = serde_json::to_string(&resume_msg).unwrap();
+                                if ws_sender.send(Message::Text(resume_json)).await.is_err() {
+                                    println!("Failed to send resume accepted");
+                                    return;
+                                }
+
+                                // `result.opponent_board` is already `None`
+                                // when the client's cached copy was already
+                                // current, so a cheap ack is all this sends
+                                // in that case.
+                                let resync_msg = result.opponent_board
+                                    .map(|board| ServerMessage::BoardSnapshot {
+                                        grid: board.grid,
+                                        score: board.score,
+                                        energy: board.energy,
+                                        version: result.board_version,
+                                    })
+                                    .unwrap_or(ServerMessage::BoardUnchanged);
+                                let resync_json = serde_json::to_string(&resync_msg).unwrap();
+                                if ws_sender.send(Message::Text(resync_json)).await.is_err() {
+                                    println!("Failed to send board resync");
+                                    return;
+                                }
+
+                                println!("Player resumed: {}", result.player.id);
+                                break (result.player.id, true);
+                            }
+                            None => {
+                                let reject_msg = ServerMessage::ResumeRejected;
+                                let reject_json = serde_json::to_string(&reject_msg).unwrap();
+                                let _ = ws_sender.send(Message::Text(reject_json)).await;
+                                println!("Resume rejected: unknown or expired session token");
                                 return;
                             }
                         }
                     }
                     Ok(_) => {
-                        // Wrong message type - expecting Login first
+                        // Wrong message type - expecting Login or Resume first
                         let reject_msg = ServerMessage::AuthRejected {
-                            reason: "Expected Login message first".to_string(),
+                            reason: "Expected Login or Resume message first".to_string(),
                         };
                         let reject_json = serde_json::to_string(&reject_msg).unwrap();
                         let _ = ws_sender.send(Message::Text(reject_json)).await;
-                        println!("Client sent non-Login message before authentication");
+                        println!("Client sent unexpected message before authentication");
                         return;
                     }
                     Err(_) => {
@@ -536,41 +1613,61 @@ async fn handle_connection(
         }
     };
 
-    // Create player with authenticated user ID
-    let player = Player {
-        id: player_id,
-        tx: tx.clone(),
-    };
-
-    // Add player to server state
-    state.add_player(player).await;
-
-    println!("Player connected and authenticated: {}", player_id);
+    // Everything from here on is scoped to this one authenticated player, so
+    // it's tagged with a single span keyed by their id - this, plus the
+    // per-game span in `run_game_actor`, is what turns the `println!`s below
+    // into events a tracing subscriber can correlate by player and by match.
+    let conn_span = tracing::info_span!("connection", player_id = %player_id);
+
+    if resumed {
+        println!("Player reconnected: {}", player_id);
+    } else {
+        // Create player with authenticated user ID and add to server state
+        let player = Player {
+            id: player_id,
+            tx: Arc::new(RwLock::new(tx.clone())),
+        };
+        state.add_player(player).await;
+        println!("Player connected and authenticated: {}", player_id);
+    }
 
     // Spawn task to send messages to client
-    let mut send_task = tokio::spawn(async move {
-        while let Some(message) = rx.recv().await {
-            if ws_sender.send(message).await.is_err() {
-                break;
+    let mut send_task = tokio::spawn(
+        async move {
+            while let Some(message) = rx.recv().await {
+                if ws_sender.send(message).await.is_err() {
+                    break;
+                }
             }
         }
-    });
+        .instrument(conn_span.clone()),
+    );
 
     // Handle incoming messages from client
     let state_clone = state.clone();
-    let mut recv_task = tokio::spawn(async move {
-        while let Some(Ok(message)) = ws_receiver.next().await {
-            if let Message::Text(text) = message {
-                if let Ok(client_msg) = serde_json::from_str::<ClientMessage>(&text) {
-                    // Skip Login messages after authentication
-                    if matches!(client_msg, ClientMessage::Login { .. }) {
-                        continue;
+    let mut recv_task = tokio::spawn(
+        async move {
+            while let Some(Ok(message)) = ws_receiver.next().await {
+                if let Message::Text(text) = message {
+                    if let Ok(client_msg) = serde_json::from_str::<ClientMessage>(&text) {
+                        // Skip handshake-only messages once already authenticated
+                        if matches!(
+                            client_msg,
+                            ClientMessage::Hello { .. }
+                                | ClientMessage::RequestServerStatus
+                                | ClientMessage::Login { .. }
+                                | ClientMessage::Register { .. }
+                                | ClientMessage::Resume { .. }
+                        ) {
+                            continue;
+                        }
+                        state_clone.handle_client_message(player_id, client_msg).await;
                     }
-                    state_clone.handle_client_message(player_id, client_msg).await;
                 }
             }
         }
-    });
+        .instrument(conn_span.clone()),
+    );
 
     // Wait for either task to finish
     tokio::select! {
@@ -587,8 +1684,45 @@ async fn handle_connection(
     println!("Player disconnected: {}", player_id);
 }
 
+/// Sets up the `tracing` subscriber that every span and event in this
+/// process (connection spans, game spans, the plain `println!`-replaced-by-`tracing::info!`
+/// call sites as they're migrated) feeds into. With the `otlp` feature off,
+/// this is just a stderr fmt layer; with it on and `OTLP_ENDPOINT` set, spans
+/// and events are also exported so they show up correlated in a tracing
+/// backend instead of as bare log lines.
+fn init_tracing() {
+    #[cfg(feature = "otlp")]
+    {
+        if let Ok(endpoint) = std::env::var("OTLP_ENDPOINT") {
+            use opentelemetry::trace::TracerProvider;
+            use tracing_subscriber::layer::SubscriberExt;
+            use tracing_subscriber::util::SubscriberInitExt;
+
+            let exporter = opentelemetry_otlp::SpanExporter::builder()
+                .with_tonic()
+                .with_endpoint(endpoint)
+                .build()
+                .expect("Failed to build OTLP exporter");
+            let provider = opentelemetry_sdk::trace::TracerProvider::builder()
+                .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+                .build();
+            let tracer = provider.tracer("match3-server");
+
+            tracing_subscriber::registry()
+                .with(tracing_subscriber::fmt::layer())
+                .with(tracing_opentelemetry::layer().with_tracer(tracer))
+                .init();
+            return;
+        }
+    }
+
+    tracing_subscriber::fmt::init();
+}
+
 #[tokio::main]
 async fn main() {
+    init_tracing();
+
     let addr = "127.0.0.1:9001";
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
 
@@ -599,6 +1733,27 @@ async fn main() {
 
     let state = ServerState::new(db);
 
+    // Only bind the inter-node cluster listener if this deployment actually
+    // declares a local cluster address; a single-node setup (the default)
+    // has no peers to talk to it.
+    if let Ok(cluster_addr) = std::env::var("CLUSTER_LISTEN_ADDR") {
+        if let Ok(socket_addr) = cluster_addr.parse() {
+            let cluster_state = state.clone();
+            tokio::spawn(cluster::run_cluster_listener(socket_addr, cluster_state));
+        } else {
+            println!("Invalid CLUSTER_LISTEN_ADDR: {}", cluster_addr);
+        }
+    }
+
+    // `/metrics` listens separately from the WebSocket port (default
+    // 127.0.0.1:9101, override via METRICS_LISTEN_ADDR) so a Prometheus
+    // scraper never shares a socket with player traffic.
+    let metrics_addr: SocketAddr = std::env::var("METRICS_LISTEN_ADDR")
+        .unwrap_or_else(|_| "127.0.0.1:9101".to_string())
+        .parse()
+        .expect("Invalid METRICS_LISTEN_ADDR");
+    tokio::spawn(metrics::run_metrics_listener(metrics_addr, state.metrics.clone()));
+
     while let Ok((stream, addr)) = listener.accept().await {
         println!("New connection from: {}", addr);
 
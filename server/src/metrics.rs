@@ -0,0 +1,89 @@
+//! Prometheus metrics for this server, served on their own HTTP listener
+//! (`METRICS_LISTEN_ADDR`, separate from the game's WebSocket port) so a
+//! scraper never competes with player traffic. Counters/gauges live on a
+//! `Metrics` handle passed around via `ServerState`, the same way every
+//! other piece of shared state here is threaded through rather than kept in
+//! a global - see `ServerState::new`.
+use prometheus::{Encoder, Histogram, HistogramOpts, IntCounter, IntGauge, Registry, TextEncoder};
+use std::net::SocketAddr;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+pub struct Metrics {
+    registry: Registry,
+    pub players_online: IntGauge,
+    pub games_active: IntGauge,
+    pub queue_length: IntGauge,
+    pub matches_completed: IntCounter,
+    pub match_duration_seconds: Histogram,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+
+        let players_online = IntGauge::new("match3_players_online", "Currently connected players").unwrap();
+        let games_active = IntGauge::new("match3_games_active", "Currently in-progress matches").unwrap();
+        let queue_length = IntGauge::new("match3_matchmaking_queue_length", "Players waiting in the matchmaking queue").unwrap();
+        let matches_completed = IntCounter::new("match3_matches_completed_total", "Matches that have run to completion").unwrap();
+        let match_duration_seconds = Histogram::with_opts(
+            HistogramOpts::new("match3_match_duration_seconds", "Wall-clock duration of completed matches")
+                .buckets(vec![15.0, 30.0, 45.0, 60.0, 75.0, 90.0, 105.0, 120.0]),
+        )
+        .unwrap();
+
+        registry.register(Box::new(players_online.clone())).unwrap();
+        registry.register(Box::new(games_active.clone())).unwrap();
+        registry.register(Box::new(queue_length.clone())).unwrap();
+        registry.register(Box::new(matches_completed.clone())).unwrap();
+        registry.register(Box::new(match_duration_seconds.clone())).unwrap();
+
+        Self { registry, players_online, games_active, queue_length, matches_completed, match_duration_seconds }
+    }
+
+    /// Renders every registered metric in Prometheus text exposition format.
+    fn render(&self) -> Vec<u8> {
+        let metric_families = self.registry.gather();
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&metric_families, &mut buffer).unwrap();
+        buffer
+    }
+}
+
+/// Minimal `/metrics` HTTP listener: every request (method and path are
+/// ignored - there's only one thing to serve) gets back the current
+/// Prometheus exposition text. Deliberately separate from the hand-rolled
+/// request parser in `cluster.rs`, since a scrape has no body to read and
+/// nothing here needs to branch on path.
+pub async fn run_metrics_listener(bind_addr: SocketAddr, metrics: std::sync::Arc<Metrics>) {
+    let listener = match TcpListener::bind(bind_addr).await {
+        Ok(l) => l,
+        Err(e) => {
+            println!("Metrics listener failed to bind {}: {}", bind_addr, e);
+            return;
+        }
+    };
+    println!("Metrics listener on {}", bind_addr);
+
+    loop {
+        let (mut stream, _) = match listener.accept().await {
+            Ok(pair) => pair,
+            Err(_) => continue,
+        };
+        let metrics = metrics.clone();
+        tokio::spawn(async move {
+            // Drain the request so a keep-alive client doesn't see a reset
+            // before it's finished sending; we don't care what it says.
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf).await;
+
+            let body = metrics.render();
+            let header = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\n\r\n",
+                body.len()
+            );
+            let _ = stream.write_all(header.as_bytes()).await;
+            let _ = stream.write_all(&body).await;
+        });
+    }
+}
@@ -0,0 +1,136 @@
+//! Sound effects and background music, gated behind the `audio` cargo
+//! feature so builds without the `assets/` audio files still compile.
+#[cfg(feature = "audio")]
+use macroquad::audio::{self, PlaySoundParams, Sound};
+
+#[cfg(feature = "audio")]
+pub struct Audio {
+    swap_click: Sound,
+    invalid_swap: Sound,
+    match_clear: Sound,
+    cascade_whoosh: Sound,
+    countdown_tick: Sound,
+    win_stinger: Sound,
+    lose_stinger: Sound,
+    music: Sound,
+    muted: bool,
+}
+
+#[cfg(feature = "audio")]
+impl Audio {
+    /// Loads every sound handle up front; call once from `main` before the
+    /// game loop starts, then starts the looping background track.
+    pub async fn load() -> Self {
+        let swap_click = audio::load_sound("assets/sfx/swap_click.ogg").await.expect("load swap_click.ogg");
+        let invalid_swap = audio::load_sound("assets/sfx/invalid_swap.ogg").await.expect("load invalid_swap.ogg");
+        let match_clear = audio::load_sound("assets/sfx/match_clear.ogg").await.expect("load match_clear.ogg");
+        let cascade_whoosh = audio::load_sound("assets/sfx/cascade_whoosh.ogg").await.expect("load cascade_whoosh.ogg");
+        let countdown_tick = audio::load_sound("assets/sfx/countdown_tick.ogg").await.expect("load countdown_tick.ogg");
+        let win_stinger = audio::load_sound("assets/sfx/win_stinger.ogg").await.expect("load win_stinger.ogg");
+        let lose_stinger = audio::load_sound("assets/sfx/lose_stinger.ogg").await.expect("load lose_stinger.ogg");
+        let music = audio::load_sound("assets/music/theme.ogg").await.expect("load theme.ogg");
+
+        audio::play_sound(
+            &music,
+            PlaySoundParams {
+                looped: true,
+                volume: 0.5,
+            },
+        );
+
+        Self {
+            swap_click,
+            invalid_swap,
+            match_clear,
+            cascade_whoosh,
+            countdown_tick,
+            win_stinger,
+            lose_stinger,
+            music,
+            muted: false,
+        }
+    }
+
+    pub fn toggle_mute(&mut self) {
+        self.muted = !self.muted;
+        audio::set_sound_volume(&self.music, if self.muted { 0.0 } else { 0.5 });
+    }
+
+    pub fn muted(&self) -> bool {
+        self.muted
+    }
+
+    fn play(&self, sound: &Sound, volume: f32) {
+        if self.muted {
+            return;
+        }
+        audio::play_sound(
+            sound,
+            PlaySoundParams {
+                looped: false,
+                volume,
+            },
+        );
+    }
+
+    pub fn play_swap_click(&self) {
+        self.play(&self.swap_click, 0.6);
+    }
+
+    pub fn play_invalid_swap(&self) {
+        self.play(&self.invalid_swap, 0.5);
+    }
+
+    pub fn play_match_clear(&self) {
+        self.play(&self.match_clear, 0.7);
+    }
+
+    /// macroquad's audio API has no pitch control, so deeper combo waves
+    /// play louder instead of higher-pitched to still read as escalating.
+    pub fn play_cascade_whoosh(&self, combo_depth: u32) {
+        let volume = (0.4 + 0.05 * combo_depth as f32).min(1.0);
+        self.play(&self.cascade_whoosh, volume);
+    }
+
+    pub fn play_countdown_tick(&self) {
+        self.play(&self.countdown_tick, 0.5);
+    }
+
+    pub fn play_win_stinger(&self) {
+        self.play(&self.win_stinger, 0.8);
+    }
+
+    pub fn play_lose_stinger(&self) {
+        self.play(&self.lose_stinger, 0.8);
+    }
+}
+
+/// No-op stand-in used when the `audio` feature is disabled, so call sites
+/// don't need to be individually `cfg`-gated.
+#[cfg(not(feature = "audio"))]
+pub struct Audio {
+    muted: bool,
+}
+
+#[cfg(not(feature = "audio"))]
+impl Audio {
+    pub async fn load() -> Self {
+        Self { muted: false }
+    }
+
+    pub fn toggle_mute(&mut self) {
+        self.muted = !self.muted;
+    }
+
+    pub fn muted(&self) -> bool {
+        self.muted
+    }
+
+    pub fn play_swap_click(&self) {}
+    pub fn play_invalid_swap(&self) {}
+    pub fn play_match_clear(&self) {}
+    pub fn play_cascade_whoosh(&self, _combo_depth: u32) {}
+    pub fn play_countdown_tick(&self) {}
+    pub fn play_win_stinger(&self) {}
+    pub fn play_lose_stinger(&self) {}
+}
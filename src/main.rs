@@ -1,11 +1,108 @@
 use macroquad::prelude::*;
 use ::rand::Rng;
 
+mod audio;
+mod seven_segment;
+
+use audio::Audio;
+
 const GRID_SIZE: usize = 8;
 const GEM_SIZE: f32 = 60.0;
 const BOARD_OFFSET_X: f32 = 50.0;
 const BOARD_OFFSET_Y: f32 = 120.0;
 const GAME_DURATION: f32 = 90.0;
+const AI_THINK_MIN: f32 = 0.6;
+const AI_THINK_MAX: f32 = 1.2;
+const COMBO_MULTIPLIER_CAP: u32 = 10;
+const COMBO_BANNER_DURATION: f32 = 1.0;
+const UNDO_STACK_CAP: usize = 5;
+const UNDO_TIME_PENALTY: f32 = 3.0;
+const UNDO_BUTTON_X: f32 = BOARD_OFFSET_X;
+const UNDO_BUTTON_Y: f32 = BOARD_OFFSET_Y + GRID_SIZE as f32 * GEM_SIZE + 20.0;
+const UNDO_BUTTON_WIDTH: f32 = 150.0;
+const UNDO_BUTTON_HEIGHT: f32 = 40.0;
+/// Fixed design resolution every draw call and hit test is authored against.
+/// The actual window is scaled and letterboxed to fit it, so the board and
+/// UI stay correctly proportioned and aligned at any window size.
+const DESIGN_WIDTH: f32 = 600.0;
+const DESIGN_HEIGHT: f32 = 800.0;
+const MUTE_BUTTON_X: f32 = DESIGN_WIDTH / 2.0 - 60.0;
+const MUTE_BUTTON_Y: f32 = DESIGN_HEIGHT / 2.0 + 150.0;
+const MUTE_BUTTON_WIDTH: f32 = 120.0;
+const MUTE_BUTTON_HEIGHT: f32 = 40.0;
+
+/// Returns the uniform scale factor and the top-left letterbox offset needed
+/// to fit the `DESIGN_WIDTH` x `DESIGN_HEIGHT` canvas into the current window
+/// without stretching or clipping, centering any leftover space.
+fn letterbox() -> (f32, f32, f32) {
+    let scale = (screen_width() / DESIGN_WIDTH).min(screen_height() / DESIGN_HEIGHT);
+    let offset_x = (screen_width() - DESIGN_WIDTH * scale) / 2.0;
+    let offset_y = (screen_height() - DESIGN_HEIGHT * scale) / 2.0;
+    (scale, offset_x, offset_y)
+}
+
+/// Converts a point in the fixed design canvas to real window pixels.
+fn world_to_screen(x: f32, y: f32) -> (f32, f32) {
+    let (scale, offset_x, offset_y) = letterbox();
+    (x * scale + offset_x, y * scale + offset_y)
+}
+
+/// Converts a window pixel (e.g. a mouse position) back to design-canvas
+/// coordinates. The inverse of `world_to_screen`, used for hit-testing.
+fn screen_to_world(x: f32, y: f32) -> (f32, f32) {
+    let (scale, offset_x, offset_y) = letterbox();
+    ((x - offset_x) / scale, (y - offset_y) / scale)
+}
+
+/// Design-space rectangle fill, scaled and letterboxed to the real window.
+fn draw_rect_w(x: f32, y: f32, w: f32, h: f32, color: Color) {
+    let (scale, offset_x, offset_y) = letterbox();
+    let (sx, sy) = (x * scale + offset_x, y * scale + offset_y);
+    draw_rectangle(sx, sy, w * scale, h * scale, color);
+}
+
+/// Design-space rectangle outline, scaled and letterboxed to the real window.
+fn draw_rect_lines_w(x: f32, y: f32, w: f32, h: f32, thickness: f32, color: Color) {
+    let (scale, offset_x, offset_y) = letterbox();
+    let (sx, sy) = (x * scale + offset_x, y * scale + offset_y);
+    draw_rectangle_lines(sx, sy, w * scale, h * scale, thickness * scale, color);
+}
+
+/// Design-space filled circle, scaled and letterboxed to the real window.
+fn draw_circle_w(x: f32, y: f32, r: f32, color: Color) {
+    let (scale, offset_x, offset_y) = letterbox();
+    let (sx, sy) = (x * scale + offset_x, y * scale + offset_y);
+    draw_circle(sx, sy, r * scale, color);
+}
+
+/// Design-space circle outline, scaled and letterboxed to the real window.
+fn draw_circle_lines_w(x: f32, y: f32, r: f32, thickness: f32, color: Color) {
+    let (scale, offset_x, offset_y) = letterbox();
+    let (sx, sy) = (x * scale + offset_x, y * scale + offset_y);
+    draw_circle_lines(sx, sy, r * scale, thickness * scale, color);
+}
+
+/// Design-space text, scaled and letterboxed to the real window.
+fn draw_text_w(text: &str, x: f32, y: f32, font_size: f32, color: Color) {
+    let (scale, offset_x, offset_y) = letterbox();
+    let (sx, sy) = (x * scale + offset_x, y * scale + offset_y);
+    draw_text(text, sx, sy, font_size * scale, color);
+}
+
+/// Design-space seven-segment LED digits, scaled and letterboxed to the real window.
+fn draw_seven_segment_w(
+    value: u32,
+    digits: usize,
+    x: f32,
+    y: f32,
+    size: f32,
+    lit_color: Color,
+    dim_color: Color,
+) {
+    let (scale, offset_x, offset_y) = letterbox();
+    let (sx, sy) = (x * scale + offset_x, y * scale + offset_y);
+    seven_segment::draw_seven_segment(value, digits, sx, sy, size * scale, lit_color, dim_color);
+}
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 enum GemType {
@@ -42,11 +139,23 @@ impl GemType {
     }
 }
 
+/// A power-up left behind by a long match, placed at the center of the run
+/// that spawned it. Persists in the grid like a regular gem until it's
+/// swapped or swept up by a later match, at which point it detonates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Special {
+    /// Clears its entire row (if `horizontal`) or column.
+    LineBlast { horizontal: bool },
+    /// Clears every gem on the board matching the color it's triggered against.
+    ColorBomb,
+}
+
 #[derive(Clone, Copy)]
 struct Gem {
     gem_type: GemType,
     y_offset: f32,
     is_falling: bool,
+    special: Option<Special>,
 }
 
 impl Gem {
@@ -55,8 +164,228 @@ impl Gem {
             gem_type,
             y_offset: 0.0,
             is_falling: false,
+            special: None,
+        }
+    }
+}
+
+/// A rewindable snapshot of the board state captured just before a
+/// player-initiated swap is committed, so it can be restored by UNDO.
+#[derive(Clone)]
+struct Snapshot {
+    grid: Vec<Vec<Option<Gem>>>,
+    score: u32,
+    combo_depth: u32,
+}
+
+/// Which axis a run of matching gems lies along, so callers can tell a
+/// horizontal run from a vertical one (e.g. to orient a line-blast special,
+/// or to detect a horizontal run crossing a vertical one at a shared cell).
+#[derive(Clone, Copy, PartialEq)]
+enum RunOrientation {
+    Horizontal,
+    Vertical,
+}
+
+/// Returns the positions of every cell, basic or not, that forms part of a
+/// match-3-or-more run in `grid`, deduplicated across overlapping horizontal
+/// and vertical runs at the same cell.
+fn find_matches(grid: &Vec<Vec<Option<Gem>>>) -> Vec<(usize, usize)> {
+    let mut seen = vec![vec![false; GRID_SIZE]; GRID_SIZE];
+    let mut out = Vec::new();
+    for (_, group) in find_match_groups(grid) {
+        for (r, c) in group {
+            if !seen[r][c] {
+                seen[r][c] = true;
+                out.push((r, c));
+            }
+        }
+    }
+    out
+}
+
+/// Returns each individual horizontal/vertical run of 3+ matching gems as its
+/// own group tagged with its orientation, so callers can tell a plain triple
+/// from a longer run (which spawns a special gem) or a crossing run (which
+/// spawns a color bomb).
+fn find_match_groups(grid: &Vec<Vec<Option<Gem>>>) -> Vec<(RunOrientation, Vec<(usize, usize)>)> {
+    let mut groups = Vec::new();
+
+    for row in 0..GRID_SIZE {
+        let mut col = 0;
+        while col < GRID_SIZE {
+            if let Some(gem) = grid[row][col] {
+                let gem_type = gem.gem_type;
+                let mut end = col + 1;
+                while end < GRID_SIZE && grid[row][end].map(|g| g.gem_type) == Some(gem_type) {
+                    end += 1;
+                }
+                if end - col >= 3 {
+                    groups.push((RunOrientation::Horizontal, (col..end).map(|c| (row, c)).collect()));
+                }
+                col = end;
+            } else {
+                col += 1;
+            }
         }
     }
+
+    for col in 0..GRID_SIZE {
+        let mut row = 0;
+        while row < GRID_SIZE {
+            if let Some(gem) = grid[row][col] {
+                let gem_type = gem.gem_type;
+                let mut end = row + 1;
+                while end < GRID_SIZE && grid[end][col].map(|g| g.gem_type) == Some(gem_type) {
+                    end += 1;
+                }
+                if end - row >= 3 {
+                    groups.push((RunOrientation::Vertical, (row..end).map(|r| (r, col)).collect()));
+                }
+                row = end;
+            } else {
+                row += 1;
+            }
+        }
+    }
+
+    groups
+}
+
+fn has_match_at(grid: &Vec<Vec<Option<Gem>>>, row: usize, col: usize) -> bool {
+    find_matches(grid).contains(&(row, col))
+}
+
+/// Settles `grid` by dropping existing gems into empty cells below them and
+/// filling the vacated top cells with fresh random gems.
+fn apply_gravity(grid: &mut Vec<Vec<Option<Gem>>>) {
+    for col in 0..GRID_SIZE {
+        let mut write_row = GRID_SIZE;
+
+        for row in (0..GRID_SIZE).rev() {
+            if grid[row][col].is_some() {
+                write_row -= 1;
+                if write_row != row {
+                    grid[write_row][col] = grid[row][col];
+                    grid[row][col] = None;
+                }
+            }
+        }
+
+        for row in 0..write_row {
+            let mut new_gem = Gem::new(GemType::random());
+            new_gem.y_offset = (write_row - row) as f32 * GEM_SIZE;
+            new_gem.is_falling = true;
+            grid[row][col] = Some(new_gem);
+        }
+    }
+}
+
+fn would_create_initial_match(grid: &Vec<Vec<Option<Gem>>>, row: usize, col: usize) -> bool {
+    if let Some(gem) = grid[row][col] {
+        let gem_type = gem.gem_type;
+
+        let mut h_count = 1;
+        if col >= 2 {
+            if let Some(g) = grid[row][col - 1] {
+                if g.gem_type == gem_type {
+                    h_count += 1;
+                    if let Some(g2) = grid[row][col - 2] {
+                        if g2.gem_type == gem_type {
+                            h_count += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut v_count = 1;
+        if row >= 2 {
+            if let Some(g) = grid[row - 1][col] {
+                if g.gem_type == gem_type {
+                    v_count += 1;
+                    if let Some(g2) = grid[row - 2][col] {
+                        if g2.gem_type == gem_type {
+                            v_count += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        h_count >= 3 || v_count >= 3
+    } else {
+        false
+    }
+}
+
+/// Fills every cell of `grid` with random gems, re-rolling any cell that
+/// would otherwise start the board already matched.
+fn initialize_board(grid: &mut Vec<Vec<Option<Gem>>>) {
+    for row in 0..GRID_SIZE {
+        for col in 0..GRID_SIZE {
+            loop {
+                grid[row][col] = Some(Gem::new(GemType::random()));
+                if !would_create_initial_match(grid, row, col) {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Swaps `(row1,col1)` with `(row2,col2)` in a scratch copy of `grid` and, if
+/// that swap produces a match, returns `(cleared, longest_run)` so callers
+/// can rank candidate moves by how much they clear and break ties toward
+/// longer runs (which spawn specials in richer variants of this board).
+fn evaluate_swap(
+    grid: &Vec<Vec<Option<Gem>>>,
+    row1: usize,
+    col1: usize,
+    row2: usize,
+    col2: usize,
+) -> Option<(u32, u32)> {
+    let mut scratch = grid.clone();
+    let temp = scratch[row1][col1];
+    scratch[row1][col1] = scratch[row2][col2];
+    scratch[row2][col2] = temp;
+
+    let groups = find_match_groups(&scratch);
+    if groups.is_empty() {
+        return None;
+    }
+
+    let cleared = find_matches(&scratch).len() as u32;
+    let longest_run = groups.iter().map(|(_, g)| g.len()).max().unwrap_or(0) as u32;
+    Some((cleared, longest_run))
+}
+
+/// Enumerates every legal adjacent swap (right and down neighbor of each
+/// cell) and returns the one that clears the most gems, breaking ties toward
+/// the longest single run. Returns `None` if no legal swap produces a match.
+fn find_best_move(grid: &Vec<Vec<Option<Gem>>>) -> Option<(usize, usize, usize, usize)> {
+    let mut best: Option<((u32, u32), (usize, usize, usize, usize))> = None;
+
+    for row in 0..GRID_SIZE {
+        for col in 0..GRID_SIZE {
+            if col + 1 < GRID_SIZE {
+                if let Some(rank) = evaluate_swap(grid, row, col, row, col + 1) {
+                    if best.as_ref().map_or(true, |(b, _)| rank > *b) {
+                        best = Some((rank, (row, col, row, col + 1)));
+                    }
+                }
+            }
+            if row + 1 < GRID_SIZE {
+                if let Some(rank) = evaluate_swap(grid, row, col, row + 1, col) {
+                    if best.as_ref().map_or(true, |(b, _)| rank > *b) {
+                        best = Some((rank, (row, col, row + 1, col)));
+                    }
+                }
+            }
+        }
+    }
+
+    best.map(|(_, mv)| mv)
 }
 
 #[derive(PartialEq)]
@@ -68,84 +397,47 @@ enum GameState {
 
 struct Game {
     grid: Vec<Vec<Option<Gem>>>,
+    opponent_grid: Vec<Vec<Option<Gem>>>,
     selected: Option<(usize, usize)>,
+    /// Cell currently hovered by the keyboard cursor, independent of `selected`.
+    cursor: (usize, usize),
     score: u32,
     opponent_score: u32,
     state: GameState,
     time_remaining: f32,
     animation_timer: f32,
+    ai_think_timer: f32,
+    combo_depth: u32,
+    combo_banner_timer: f32,
+    undo_stack: Vec<Snapshot>,
+    audio: Audio,
 }
 
 impl Game {
-    fn new() -> Self {
+    fn new(audio: Audio) -> Self {
         let mut game = Self {
             grid: vec![vec![None; GRID_SIZE]; GRID_SIZE],
+            opponent_grid: vec![vec![None; GRID_SIZE]; GRID_SIZE],
             selected: None,
+            cursor: (0, 0),
             score: 0,
             opponent_score: 0,
             state: GameState::Menu,
             time_remaining: GAME_DURATION,
             animation_timer: 0.0,
+            ai_think_timer: AI_THINK_MIN,
+            combo_depth: 0,
+            combo_banner_timer: 0.0,
+            undo_stack: Vec::new(),
+            audio,
         };
         game.initialize_board();
         game
     }
 
     fn initialize_board(&mut self) {
-        // Fill board with random gems, avoiding initial matches
-        for row in 0..GRID_SIZE {
-            for col in 0..GRID_SIZE {
-                loop {
-                    let gem = Gem::new(GemType::random());
-                    self.grid[row][col] = Some(gem);
-
-                    // Check if this creates a match-3
-                    if !self.would_create_initial_match(row, col) {
-                        break;
-                    }
-                }
-            }
-        }
-    }
-
-    fn would_create_initial_match(&self, row: usize, col: usize) -> bool {
-        if let Some(gem) = self.grid[row][col] {
-            let gem_type = gem.gem_type;
-
-            // Check horizontal
-            let mut h_count = 1;
-            if col >= 2 {
-                if let Some(g) = self.grid[row][col - 1] {
-                    if g.gem_type == gem_type {
-                        h_count += 1;
-                        if let Some(g2) = self.grid[row][col - 2] {
-                            if g2.gem_type == gem_type {
-                                h_count += 1;
-                            }
-                        }
-                    }
-                }
-            }
-
-            // Check vertical
-            let mut v_count = 1;
-            if row >= 2 {
-                if let Some(g) = self.grid[row - 1][col] {
-                    if g.gem_type == gem_type {
-                        v_count += 1;
-                        if let Some(g2) = self.grid[row - 2][col] {
-                            if g2.gem_type == gem_type {
-                                v_count += 1;
-                            }
-                        }
-                    }
-                }
-            }
-
-            h_count >= 3 || v_count >= 3
-        } else {
-            false
-        }
+        initialize_board(&mut self.grid);
+        initialize_board(&mut self.opponent_grid);
     }
 
     fn start_game(&mut self) {
@@ -154,16 +446,67 @@ impl Game {
         self.opponent_score = 0;
         self.time_remaining = GAME_DURATION;
         self.selected = None;
+        self.cursor = (0, 0);
+        self.ai_think_timer = AI_THINK_MIN;
+        self.combo_depth = 0;
+        self.combo_banner_timer = 0.0;
+        self.undo_stack.clear();
         self.initialize_board();
     }
 
+    /// Plays the opponent's best available move against `opponent_grid`,
+    /// resolving gravity and cascades synchronously (the opponent board
+    /// isn't rendered, so there's no need to animate it frame by frame) and
+    /// crediting `opponent_score` for every cell actually cleared. If no
+    /// move produces a match, the board is reshuffled the same way a fresh
+    /// board avoids starting matches.
+    fn ai_take_turn(&mut self) {
+        let Some((row1, col1, row2, col2)) = find_best_move(&self.opponent_grid) else {
+            initialize_board(&mut self.opponent_grid);
+            return;
+        };
+
+        let temp = self.opponent_grid[row1][col1];
+        self.opponent_grid[row1][col1] = self.opponent_grid[row2][col2];
+        self.opponent_grid[row2][col2] = temp;
+
+        loop {
+            let matches = find_matches(&self.opponent_grid);
+            if matches.is_empty() {
+                break;
+            }
+
+            let cleared = matches.len() as u32;
+            for &(r, c) in &matches {
+                self.opponent_grid[r][c] = None;
+            }
+
+            self.opponent_score += cleared * 10;
+            if cleared >= 4 {
+                self.opponent_score += 20;
+            }
+
+            apply_gravity(&mut self.opponent_grid);
+        }
+    }
+
     fn update(&mut self, dt: f32) {
         match self.state {
             GameState::Playing => {
+                let prev_time_remaining = self.time_remaining;
                 self.time_remaining -= dt;
                 if self.time_remaining <= 0.0 {
                     self.time_remaining = 0.0;
                     self.state = GameState::GameOver;
+                    if self.score >= self.opponent_score {
+                        self.audio.play_win_stinger();
+                    } else {
+                        self.audio.play_lose_stinger();
+                    }
+                } else if self.time_remaining <= 10.0
+                    && prev_time_remaining.ceil() != self.time_remaining.ceil()
+                {
+                    self.audio.play_countdown_tick();
                 }
 
                 // Update animations
@@ -174,9 +517,15 @@ impl Game {
                     self.update_falling_gems(dt);
                 }
 
-                // Simulate opponent score increasing
-                if ::rand::random::<f32>() < 0.01 {
-                    self.opponent_score += ::rand::thread_rng().gen_range(10..50);
+                if self.combo_banner_timer > 0.0 {
+                    self.combo_banner_timer -= dt;
+                }
+
+                // Drive the opponent's greedy match-3 AI off a throttled think timer
+                self.ai_think_timer -= dt;
+                if self.ai_think_timer <= 0.0 {
+                    self.ai_think_timer = ::rand::thread_rng().gen_range(AI_THINK_MIN..AI_THINK_MAX);
+                    self.ai_take_turn();
                 }
             }
             _ => {}
@@ -207,11 +556,14 @@ impl Game {
         }
     }
 
+    /// `x`/`y` are raw window pixel coordinates (e.g. straight from
+    /// `mouse_position()`); they're mapped into the design canvas internally.
     fn handle_click(&mut self, x: f32, y: f32) {
         if self.state != GameState::Playing || self.animation_timer > 0.0 {
             return;
         }
 
+        let (x, y) = screen_to_world(x, y);
         let col = ((x - BOARD_OFFSET_X) / GEM_SIZE) as i32;
         let row = ((y - BOARD_OFFSET_Y) / GEM_SIZE) as i32;
 
@@ -220,9 +572,12 @@ impl Game {
             return;
         }
 
-        let col = col as usize;
-        let row = row as usize;
+        self.select_cell(row as usize, col as usize);
+    }
 
+    /// Selects `(row, col)`, or swaps it with the existing selection if the
+    /// two are adjacent. Shared by mouse clicks and the keyboard cursor.
+    fn select_cell(&mut self, row: usize, col: usize) {
         if let Some((sel_row, sel_col)) = self.selected {
             // Check if clicked gem is adjacent
             let is_adjacent = (sel_row == row && (sel_col as i32 - col as i32).abs() == 1)
@@ -239,18 +594,71 @@ impl Game {
         }
     }
 
+    /// Moves the keyboard cursor within grid bounds; clamps rather than wraps.
+    fn move_cursor(&mut self, d_row: i32, d_col: i32) {
+        if self.state != GameState::Playing {
+            return;
+        }
+        let row = (self.cursor.0 as i32 + d_row).clamp(0, GRID_SIZE as i32 - 1) as usize;
+        let col = (self.cursor.1 as i32 + d_col).clamp(0, GRID_SIZE as i32 - 1) as usize;
+        self.cursor = (row, col);
+    }
+
+    /// Confirms the cell under the keyboard cursor, mirroring a mouse click on it.
+    fn confirm_cursor(&mut self) {
+        if self.state != GameState::Playing || self.animation_timer > 0.0 {
+            return;
+        }
+        let (row, col) = self.cursor;
+        self.select_cell(row, col);
+    }
+
     fn swap_gems(&mut self, row1: usize, col1: usize, row2: usize, col2: usize) {
+        let pre_swap = Snapshot {
+            grid: self.grid.clone(),
+            score: self.score,
+            combo_depth: self.combo_depth,
+        };
+
         let temp = self.grid[row1][col1];
         self.grid[row1][col1] = self.grid[row2][col2];
         self.grid[row2][col2] = temp;
 
+        // A special gem detonates the instant it's swapped, against the
+        // color of whatever it was swapped with, regardless of whether the
+        // swap also formed an ordinary match.
+        let gem_at_1 = self.grid[row1][col1];
+        let gem_at_2 = self.grid[row2][col2];
+        let mut cleared_by_special = 0u32;
+        if let Some(special) = gem_at_1.and_then(|g| g.special) {
+            cleared_by_special +=
+                self.activate_special_gem(row1, col1, special, gem_at_2.map(|g| g.gem_type));
+        }
+        if let Some(special) = gem_at_2.and_then(|g| g.special) {
+            cleared_by_special +=
+                self.activate_special_gem(row2, col2, special, gem_at_1.map(|g| g.gem_type));
+        }
+
+        if cleared_by_special > 0 {
+            self.audio.play_swap_click();
+            self.push_undo_snapshot(pre_swap);
+            self.score += cleared_by_special * 15;
+            self.combo_depth = 0;
+            self.apply_gravity();
+            return;
+        }
+
         // Check if swap creates matches
-        let has_match = self.has_match_at(row1, col1) || self.has_match_at(row2, col2);
+        let has_match = has_match_at(&self.grid, row1, col1) || has_match_at(&self.grid, row2, col2);
 
         if has_match {
+            self.audio.play_swap_click();
+            self.push_undo_snapshot(pre_swap);
             self.animation_timer = 0.3;
+            self.combo_depth = 0;
             self.check_and_remove_matches();
         } else {
+            self.audio.play_invalid_swap();
             // Swap back if no match
             let temp = self.grid[row1][col1];
             self.grid[row1][col1] = self.grid[row2][col2];
@@ -258,180 +666,181 @@ impl Game {
         }
     }
 
-    fn has_match_at(&self, row: usize, col: usize) -> bool {
-        if self.grid[row][col].is_none() {
-            return false;
+    fn push_undo_snapshot(&mut self, snapshot: Snapshot) {
+        self.undo_stack.push(snapshot);
+        if self.undo_stack.len() > UNDO_STACK_CAP {
+            self.undo_stack.remove(0);
         }
+    }
 
-        let gem_type = self.grid[row][col].unwrap().gem_type;
-
-        // Check horizontal
-        let mut h_count = 1;
-        // Count left
-        let mut c = col as i32 - 1;
-        while c >= 0 {
-            if let Some(g) = self.grid[row][c as usize] {
-                if g.gem_type == gem_type {
-                    h_count += 1;
-                    c -= 1;
+    /// Detonates the special gem at `(row, col)`, clearing it and whatever
+    /// it affects, and returns the total number of cells cleared. `target`
+    /// is the color of the gem it was swapped against, which a color bomb
+    /// uses to pick what to clear.
+    fn activate_special_gem(
+        &mut self,
+        row: usize,
+        col: usize,
+        special: Special,
+        target: Option<GemType>,
+    ) -> u32 {
+        self.grid[row][col] = None;
+        let mut cleared = 1;
+
+        match special {
+            Special::LineBlast { horizontal } => {
+                if horizontal {
+                    for c in 0..GRID_SIZE {
+                        if self.grid[row][c].take().is_some() {
+                            cleared += 1;
+                        }
+                    }
                 } else {
-                    break;
+                    for r in 0..GRID_SIZE {
+                        if self.grid[r][col].take().is_some() {
+                            cleared += 1;
+                        }
+                    }
                 }
-            } else {
-                break;
             }
-        }
-        // Count right
-        let mut c = col + 1;
-        while c < GRID_SIZE {
-            if let Some(g) = self.grid[row][c] {
-                if g.gem_type == gem_type {
-                    h_count += 1;
-                    c += 1;
-                } else {
-                    break;
+            Special::ColorBomb => {
+                if let Some(target) = target {
+                    for r in 0..GRID_SIZE {
+                        for c in 0..GRID_SIZE {
+                            if self.grid[r][c].map(|g| g.gem_type) == Some(target) {
+                                self.grid[r][c] = None;
+                                cleared += 1;
+                            }
+                        }
+                    }
                 }
-            } else {
-                break;
             }
         }
 
-        if h_count >= 3 {
-            return true;
-        }
+        cleared
+    }
 
-        // Check vertical
-        let mut v_count = 1;
-        // Count up
-        let mut r = row as i32 - 1;
-        while r >= 0 {
-            if let Some(g) = self.grid[r as usize][col] {
-                if g.gem_type == gem_type {
-                    v_count += 1;
-                    r -= 1;
-                } else {
-                    break;
-                }
-            } else {
-                break;
-            }
-        }
-        // Count down
-        let mut r = row + 1;
-        while r < GRID_SIZE {
-            if let Some(g) = self.grid[r][col] {
-                if g.gem_type == gem_type {
-                    v_count += 1;
-                    r += 1;
-                } else {
-                    break;
-                }
-            } else {
-                break;
-            }
+    /// Reverts the most recent committed swap, restoring the grid, score,
+    /// and combo depth from before it, at the cost of `UNDO_TIME_PENALTY`
+    /// seconds off the clock. A no-op while an animation is in flight or
+    /// once the undo stack is empty, so it can't be spammed.
+    fn undo_last_swap(&mut self) {
+        if self.animation_timer > 0.0 {
+            return;
         }
 
-        v_count >= 3
+        if let Some(snapshot) = self.undo_stack.pop() {
+            self.grid = snapshot.grid;
+            self.score = snapshot.score;
+            self.combo_depth = snapshot.combo_depth;
+            self.selected = None;
+            self.time_remaining = (self.time_remaining - UNDO_TIME_PENALTY).max(0.0);
+        }
     }
 
     fn check_and_remove_matches(&mut self) {
-        let mut to_remove = vec![vec![false; GRID_SIZE]; GRID_SIZE];
-        let mut total_matches = 0;
+        let groups = find_match_groups(&self.grid);
 
-        // Find all matches
-        for row in 0..GRID_SIZE {
-            for col in 0..GRID_SIZE {
-                if self.grid[row][col].is_none() {
-                    continue;
-                }
+        if groups.is_empty() {
+            self.combo_depth = 0;
+            return;
+        }
 
-                let gem_type = self.grid[row][col].unwrap().gem_type;
+        // Each wave of a cascade chain scores at a higher multiplier than the last
+        self.combo_depth += 1;
+        let multiplier = self.combo_depth.min(COMBO_MULTIPLIER_CAP);
 
-                // Check horizontal matches
-                let mut h_matches = vec![(row, col)];
-                for c in (col + 1)..GRID_SIZE {
-                    if let Some(g) = self.grid[row][c] {
-                        if g.gem_type == gem_type {
-                            h_matches.push((row, c));
-                        } else {
-                            break;
-                        }
-                    } else {
-                        break;
-                    }
-                }
+        // The first wave after a swap is a plain match clear; every wave
+        // after that is a cascade, whooshing louder the deeper the combo.
+        if self.combo_depth <= 1 {
+            self.audio.play_match_clear();
+        } else {
+            self.audio.play_cascade_whoosh(self.combo_depth);
+        }
 
-                if h_matches.len() >= 3 {
-                    for &(r, c) in &h_matches {
-                        to_remove[r][c] = true;
-                    }
+        // A cell that's part of both a horizontal and a vertical run forms
+        // an L/T-shaped crossing, which spawns a color bomb the same way a
+        // straight run of 5 does.
+        let mut in_horizontal = vec![vec![false; GRID_SIZE]; GRID_SIZE];
+        let mut in_vertical = vec![vec![false; GRID_SIZE]; GRID_SIZE];
+        for (orientation, positions) in &groups {
+            for &(r, c) in positions {
+                match orientation {
+                    RunOrientation::Horizontal => in_horizontal[r][c] = true,
+                    RunOrientation::Vertical => in_vertical[r][c] = true,
                 }
+            }
+        }
 
-                // Check vertical matches
-                let mut v_matches = vec![(row, col)];
-                for r in (row + 1)..GRID_SIZE {
-                    if let Some(g) = self.grid[r][col] {
-                        if g.gem_type == gem_type {
-                            v_matches.push((r, col));
-                        } else {
-                            break;
-                        }
-                    } else {
-                        break;
-                    }
-                }
+        let mut cleared = vec![vec![false; GRID_SIZE]; GRID_SIZE];
+        let mut spawns: Vec<(usize, usize, GemType, Special)> = Vec::new();
+
+        for (orientation, positions) in &groups {
+            let crossing = positions.iter().copied().find(|&(r, c)| match orientation {
+                RunOrientation::Horizontal => in_vertical[r][c],
+                RunOrientation::Vertical => in_horizontal[r][c],
+            });
+
+            let spawn = if positions.len() >= 5 {
+                Some((positions[positions.len() / 2], Special::ColorBomb))
+            } else if let Some(cross_cell) = crossing {
+                Some((cross_cell, Special::ColorBomb))
+            } else if positions.len() == 4 {
+                let horizontal = *orientation == RunOrientation::Horizontal;
+                Some((positions[positions.len() / 2], Special::LineBlast { horizontal }))
+            } else {
+                None
+            };
 
-                if v_matches.len() >= 3 {
-                    for &(r, c) in &v_matches {
-                        to_remove[r][c] = true;
-                    }
+            for &(r, c) in positions {
+                if spawn.map_or(false, |(cell, _)| cell == (r, c)) {
+                    continue;
                 }
+                cleared[r][c] = true;
+            }
+
+            if let Some((cell, special)) = spawn {
+                let gem_type = self.grid[cell.0][cell.1].map(|g| g.gem_type).unwrap_or(GemType::Red);
+                spawns.push((cell.0, cell.1, gem_type, special));
             }
         }
 
-        // Remove matched gems and count
+        let mut total_matches = 0u32;
+        let mut swept_specials = Vec::new();
         for row in 0..GRID_SIZE {
             for col in 0..GRID_SIZE {
-                if to_remove[row][col] {
-                    self.grid[row][col] = None;
+                if cleared[row][col] {
                     total_matches += 1;
+                    if let Some(Gem { gem_type, special: Some(special), .. }) = self.grid[row][col] {
+                        swept_specials.push((row, col, special, gem_type));
+                    }
+                    self.grid[row][col] = None;
                 }
             }
         }
 
-        if total_matches > 0 {
-            self.score += total_matches * 10;
-            if total_matches >= 4 {
-                self.score += 20; // Bonus for 4+ matches
-            }
-            self.apply_gravity();
+        // A special swept up by this match detonates against its own color;
+        // its own cell was already counted above.
+        for (row, col, special, gem_type) in swept_specials {
+            total_matches += self.activate_special_gem(row, col, special, Some(gem_type)) - 1;
         }
-    }
 
-    fn apply_gravity(&mut self) {
-        for col in 0..GRID_SIZE {
-            let mut write_row = GRID_SIZE;
-
-            // Move existing gems down
-            for row in (0..GRID_SIZE).rev() {
-                if self.grid[row][col].is_some() {
-                    write_row -= 1;
-                    if write_row != row {
-                        self.grid[write_row][col] = self.grid[row][col];
-                        self.grid[row][col] = None;
-                    }
-                }
-            }
+        for (row, col, gem_type, special) in spawns {
+            let mut gem = Gem::new(gem_type);
+            gem.special = Some(special);
+            self.grid[row][col] = Some(gem);
+        }
 
-            // Fill empty spaces at top with new gems
-            for row in 0..write_row {
-                let mut new_gem = Gem::new(GemType::random());
-                new_gem.y_offset = (write_row - row) as f32 * GEM_SIZE;
-                new_gem.is_falling = true;
-                self.grid[row][col] = Some(new_gem);
-            }
+        self.score += total_matches * 10 * multiplier;
+        if total_matches >= 4 {
+            self.score += 20; // Bonus for 4+ matches
         }
+        self.combo_banner_timer = COMBO_BANNER_DURATION;
+        self.apply_gravity();
+    }
 
+    fn apply_gravity(&mut self) {
+        apply_gravity(&mut self.grid);
         self.animation_timer = 0.3;
     }
 
@@ -452,87 +861,91 @@ impl Game {
     }
 
     fn draw_menu(&self) {
-        let screen_width = screen_width();
-        let screen_height = screen_height();
-
-        draw_text(
+        draw_text_w(
             "MATCH 3 PVP",
-            screen_width / 2.0 - 150.0,
-            screen_height / 2.0 - 100.0,
+            DESIGN_WIDTH / 2.0 - 150.0,
+            DESIGN_HEIGHT / 2.0 - 100.0,
             60.0,
             WHITE,
         );
 
-        draw_text(
+        draw_text_w(
             "Real-time Match-3 Battle",
-            screen_width / 2.0 - 120.0,
-            screen_height / 2.0 - 40.0,
+            DESIGN_WIDTH / 2.0 - 120.0,
+            DESIGN_HEIGHT / 2.0 - 40.0,
             25.0,
             LIGHTGRAY,
         );
 
-        draw_text(
+        draw_text_w(
             "Match 3 or more gems to score points!",
-            screen_width / 2.0 - 180.0,
-            screen_height / 2.0,
+            DESIGN_WIDTH / 2.0 - 180.0,
+            DESIGN_HEIGHT / 2.0,
             20.0,
             LIGHTGRAY,
         );
 
-        draw_text(
+        draw_text_w(
             "You have 90 seconds to beat your opponent!",
-            screen_width / 2.0 - 200.0,
-            screen_height / 2.0 + 30.0,
+            DESIGN_WIDTH / 2.0 - 200.0,
+            DESIGN_HEIGHT / 2.0 + 30.0,
             20.0,
             LIGHTGRAY,
         );
 
         // Draw start button
-        let button_x = screen_width / 2.0 - 100.0;
-        let button_y = screen_height / 2.0 + 80.0;
-        draw_rectangle(button_x, button_y, 200.0, 50.0, GREEN);
-        draw_text("START GAME", button_x + 30.0, button_y + 33.0, 30.0, WHITE);
+        let button_x = DESIGN_WIDTH / 2.0 - 100.0;
+        let button_y = DESIGN_HEIGHT / 2.0 + 80.0;
+        draw_rect_w(button_x, button_y, 200.0, 50.0, GREEN);
+        draw_text_w("START GAME", button_x + 30.0, button_y + 33.0, 30.0, WHITE);
+
+        // Draw mute toggle
+        draw_rect_w(MUTE_BUTTON_X, MUTE_BUTTON_Y, MUTE_BUTTON_WIDTH, MUTE_BUTTON_HEIGHT, GRAY);
+        let mute_label = if self.audio.muted() { "UNMUTE" } else { "MUTE" };
+        draw_text_w(mute_label, MUTE_BUTTON_X + 20.0, MUTE_BUTTON_Y + 26.0, 20.0, WHITE);
     }
 
     fn draw_game(&self) {
         // Draw header background
-        draw_rectangle(0.0, 0.0, screen_width(), 100.0, Color::from_rgba(30, 30, 60, 255));
+        draw_rect_w(0.0, 0.0, DESIGN_WIDTH, 100.0, Color::from_rgba(30, 30, 60, 255));
 
-        // Draw timer
+        // Draw timer as seven-segment LED digits, the whole display flashing
+        // red in the final 20 seconds for an arcade countdown feel.
         let minutes = (self.time_remaining / 60.0) as u32;
         let seconds = (self.time_remaining % 60.0) as u32;
-        let timer_text = format!("Time: {:02}:{:02}", minutes, seconds);
         let timer_color = if self.time_remaining < 20.0 { RED } else { WHITE };
-        draw_text(&timer_text, 20.0, 40.0, 40.0, timer_color);
-
-        // Draw scores
-        draw_text(
-            &format!("Your Score: {}", self.score),
-            20.0,
-            80.0,
-            30.0,
-            YELLOW,
-        );
-
-        draw_text(
-            &format!("Opponent: {}", self.opponent_score),
-            screen_width() - 250.0,
-            40.0,
-            30.0,
+        let dim_segment = Color::from_rgba(40, 40, 40, 255);
+        draw_text_w("TIME", 20.0, 18.0, 14.0, LIGHTGRAY);
+        draw_seven_segment_w(minutes, 2, 20.0, 22.0, 28.0, timer_color, dim_segment);
+        draw_text_w(":", 66.0, 48.0, 28.0, timer_color);
+        draw_seven_segment_w(seconds, 2, 76.0, 22.0, 28.0, timer_color, dim_segment);
+
+        // Draw scores as seven-segment LED digits
+        draw_text_w("SCORE", 20.0, 72.0, 14.0, LIGHTGRAY);
+        draw_seven_segment_w(self.score, 6, 20.0, 76.0, 18.0, YELLOW, dim_segment);
+
+        draw_text_w("OPPONENT", DESIGN_WIDTH - 160.0, 18.0, 14.0, LIGHTGRAY);
+        draw_seven_segment_w(
+            self.opponent_score,
+            6,
+            DESIGN_WIDTH - 160.0,
+            22.0,
+            18.0,
             Color::from_rgba(255, 100, 100, 255),
+            dim_segment,
         );
 
         // Draw winning/losing indicator
         if self.score > self.opponent_score {
-            draw_text("WINNING!", screen_width() - 250.0, 75.0, 25.0, GREEN);
+            draw_text_w("WINNING!", DESIGN_WIDTH - 160.0, 70.0, 20.0, GREEN);
         } else if self.score < self.opponent_score {
-            draw_text("LOSING!", screen_width() - 250.0, 75.0, 25.0, RED);
+            draw_text_w("LOSING!", DESIGN_WIDTH - 160.0, 70.0, 20.0, RED);
         } else {
-            draw_text("TIED!", screen_width() - 250.0, 75.0, 25.0, YELLOW);
+            draw_text_w("TIED!", DESIGN_WIDTH - 160.0, 70.0, 20.0, YELLOW);
         }
 
         // Draw grid background
-        draw_rectangle(
+        draw_rect_w(
             BOARD_OFFSET_X - 10.0,
             BOARD_OFFSET_Y - 10.0,
             GRID_SIZE as f32 * GEM_SIZE + 20.0,
@@ -540,6 +953,35 @@ impl Game {
             Color::from_rgba(40, 40, 70, 255),
         );
 
+        // Undo button: greyed out once the stack is empty or mid-animation
+        let undo_available = !self.undo_stack.is_empty() && self.animation_timer <= 0.0;
+        let undo_color = if undo_available {
+            Color::from_rgba(200, 100, 50, 255)
+        } else {
+            Color::from_rgba(80, 80, 80, 255)
+        };
+        draw_rect_w(UNDO_BUTTON_X, UNDO_BUTTON_Y, UNDO_BUTTON_WIDTH, UNDO_BUTTON_HEIGHT, undo_color);
+        draw_text_w(
+            &format!("UNDO (-{}s)", UNDO_TIME_PENALTY as u32),
+            UNDO_BUTTON_X + 10.0,
+            UNDO_BUTTON_Y + 26.0,
+            20.0,
+            WHITE,
+        );
+
+        // Floating combo banner for cascade chains
+        if self.combo_banner_timer > 0.0 && self.combo_depth >= 2 {
+            let alpha = (self.combo_banner_timer / COMBO_BANNER_DURATION).min(1.0);
+            let combo_text = format!("x{} COMBO!", self.combo_depth);
+            draw_text_w(
+                &combo_text,
+                BOARD_OFFSET_X + GRID_SIZE as f32 * GEM_SIZE / 2.0 - 80.0,
+                BOARD_OFFSET_Y - 20.0,
+                36.0,
+                Color::from_rgba(255, 220, 0, (255.0 * alpha) as u8),
+            );
+        }
+
         // Draw gems
         for row in 0..GRID_SIZE {
             for col in 0..GRID_SIZE {
@@ -547,7 +989,7 @@ impl Game {
                 let y = BOARD_OFFSET_Y + row as f32 * GEM_SIZE;
 
                 // Draw cell background
-                draw_rectangle(
+                draw_rect_w(
                     x + 2.0,
                     y + 2.0,
                     GEM_SIZE - 4.0,
@@ -559,7 +1001,7 @@ impl Game {
                     let gem_y = y + gem.y_offset;
 
                     // Draw gem
-                    draw_circle(
+                    draw_circle_w(
                         x + GEM_SIZE / 2.0,
                         gem_y + GEM_SIZE / 2.0,
                         GEM_SIZE / 2.5,
@@ -567,18 +1009,54 @@ impl Game {
                     );
 
                     // Draw gem highlight
-                    draw_circle(
+                    draw_circle_w(
                         x + GEM_SIZE / 2.0 - 8.0,
                         gem_y + GEM_SIZE / 2.0 - 8.0,
                         GEM_SIZE / 8.0,
                         Color::from_rgba(255, 255, 255, 150),
                     );
+
+                    match gem.special {
+                        Some(Special::LineBlast { horizontal }) => {
+                            // Striped overlay running the direction the blast will clear
+                            let cx = x + GEM_SIZE / 2.0;
+                            let cy = gem_y + GEM_SIZE / 2.0;
+                            if horizontal {
+                                draw_rect_w(
+                                    x + 4.0,
+                                    cy - 4.0,
+                                    GEM_SIZE - 8.0,
+                                    8.0,
+                                    Color::from_rgba(255, 255, 255, 220),
+                                );
+                            } else {
+                                draw_rect_w(
+                                    cx - 4.0,
+                                    gem_y + 4.0,
+                                    8.0,
+                                    GEM_SIZE - 8.0,
+                                    Color::from_rgba(255, 255, 255, 220),
+                                );
+                            }
+                        }
+                        Some(Special::ColorBomb) => {
+                            // Glowing ring to mark a color bomb
+                            draw_circle_lines_w(
+                                x + GEM_SIZE / 2.0,
+                                gem_y + GEM_SIZE / 2.0,
+                                GEM_SIZE / 2.5 + 4.0,
+                                3.0,
+                                WHITE,
+                            );
+                        }
+                        None => {}
+                    }
                 }
 
                 // Highlight selected gem
                 if let Some((sel_row, sel_col)) = self.selected {
                     if sel_row == row && sel_col == col {
-                        draw_rectangle_lines(
+                        draw_rect_lines_w(
                             x,
                             y,
                             GEM_SIZE,
@@ -588,6 +1066,18 @@ impl Game {
                         );
                     }
                 }
+
+                // Highlight the keyboard cursor (distinct from the yellow selection box)
+                if self.cursor == (row, col) {
+                    draw_rect_lines_w(
+                        x,
+                        y,
+                        GEM_SIZE,
+                        GEM_SIZE,
+                        3.0,
+                        SKYBLUE,
+                    );
+                }
             }
         }
     }
@@ -596,7 +1086,9 @@ impl Game {
         // Draw the final board state
         self.draw_game();
 
-        // Draw semi-transparent overlay
+        // Draw semi-transparent overlay over the whole real window, including
+        // any letterbox bars (unlike every other draw call here, this one is
+        // intentionally not routed through world_to_screen).
         draw_rectangle(
             0.0,
             0.0,
@@ -605,14 +1097,11 @@ impl Game {
             Color::from_rgba(0, 0, 0, 200),
         );
 
-        let screen_width = screen_width();
-        let screen_height = screen_height();
-
         // Draw game over text
-        draw_text(
+        draw_text_w(
             "GAME OVER",
-            screen_width / 2.0 - 150.0,
-            screen_height / 2.0 - 120.0,
+            DESIGN_WIDTH / 2.0 - 150.0,
+            DESIGN_HEIGHT / 2.0 - 120.0,
             60.0,
             WHITE,
         );
@@ -634,36 +1123,55 @@ impl Game {
             YELLOW
         };
 
-        draw_text(
+        draw_text_w(
             result_text,
-            screen_width / 2.0 - 100.0,
-            screen_height / 2.0 - 50.0,
+            DESIGN_WIDTH / 2.0 - 100.0,
+            DESIGN_HEIGHT / 2.0 - 50.0,
             50.0,
             result_color,
         );
 
-        // Draw final scores
-        draw_text(
-            &format!("Your Score: {}", self.score),
-            screen_width / 2.0 - 120.0,
-            screen_height / 2.0 + 20.0,
-            35.0,
+        // Draw final scores as seven-segment LED digits
+        let dim_segment = Color::from_rgba(60, 60, 60, 255);
+        draw_text_w(
+            "YOUR SCORE",
+            DESIGN_WIDTH / 2.0 - 120.0,
+            DESIGN_HEIGHT / 2.0 + 5.0,
+            20.0,
             YELLOW,
         );
+        draw_seven_segment_w(
+            self.score,
+            6,
+            DESIGN_WIDTH / 2.0 - 120.0,
+            DESIGN_HEIGHT / 2.0 + 10.0,
+            26.0,
+            YELLOW,
+            dim_segment,
+        );
 
-        draw_text(
-            &format!("Opponent Score: {}", self.opponent_score),
-            screen_width / 2.0 - 150.0,
-            screen_height / 2.0 + 60.0,
-            35.0,
+        draw_text_w(
+            "OPPONENT SCORE",
+            DESIGN_WIDTH / 2.0 - 150.0,
+            DESIGN_HEIGHT / 2.0 + 50.0,
+            20.0,
             Color::from_rgba(255, 100, 100, 255),
         );
+        draw_seven_segment_w(
+            self.opponent_score,
+            6,
+            DESIGN_WIDTH / 2.0 - 150.0,
+            DESIGN_HEIGHT / 2.0 + 55.0,
+            26.0,
+            Color::from_rgba(255, 100, 100, 255),
+            dim_segment,
+        );
 
         // Draw play again button
-        let button_x = screen_width / 2.0 - 100.0;
-        let button_y = screen_height / 2.0 + 120.0;
-        draw_rectangle(button_x, button_y, 200.0, 50.0, GREEN);
-        draw_text("PLAY AGAIN", button_x + 30.0, button_y + 33.0, 30.0, WHITE);
+        let button_x = DESIGN_WIDTH / 2.0 - 100.0;
+        let button_y = DESIGN_HEIGHT / 2.0 + 120.0;
+        draw_rect_w(button_x, button_y, 200.0, 50.0, GREEN);
+        draw_text_w("PLAY AGAIN", button_x + 30.0, button_y + 33.0, 30.0, WHITE);
     }
 }
 
@@ -678,7 +1186,8 @@ fn window_conf() -> Conf {
 
 #[macroquad::main(window_conf)]
 async fn main() {
-    let mut game = Game::new();
+    let audio = Audio::load().await;
+    let mut game = Game::new(audio);
 
     loop {
         let dt = get_frame_time();
@@ -687,13 +1196,12 @@ async fn main() {
         // Handle input
         if is_mouse_button_pressed(MouseButton::Left) {
             let (mouse_x, mouse_y) = mouse_position();
+            let (mouse_x, mouse_y) = screen_to_world(mouse_x, mouse_y);
 
             match game.state {
                 GameState::Menu => {
-                    let screen_width = screen_width();
-                    let screen_height = screen_height();
-                    let button_x = screen_width / 2.0 - 100.0;
-                    let button_y = screen_height / 2.0 + 80.0;
+                    let button_x = DESIGN_WIDTH / 2.0 - 100.0;
+                    let button_y = DESIGN_HEIGHT / 2.0 + 80.0;
 
                     if mouse_x >= button_x
                         && mouse_x <= button_x + 200.0
@@ -701,16 +1209,31 @@ async fn main() {
                         && mouse_y <= button_y + 50.0
                     {
                         game.start_game();
+                    } else if mouse_x >= MUTE_BUTTON_X
+                        && mouse_x <= MUTE_BUTTON_X + MUTE_BUTTON_WIDTH
+                        && mouse_y >= MUTE_BUTTON_Y
+                        && mouse_y <= MUTE_BUTTON_Y + MUTE_BUTTON_HEIGHT
+                    {
+                        game.audio.toggle_mute();
                     }
                 }
                 GameState::Playing => {
-                    game.handle_click(mouse_x, mouse_y);
+                    if mouse_x >= UNDO_BUTTON_X
+                        && mouse_x <= UNDO_BUTTON_X + UNDO_BUTTON_WIDTH
+                        && mouse_y >= UNDO_BUTTON_Y
+                        && mouse_y <= UNDO_BUTTON_Y + UNDO_BUTTON_HEIGHT
+                    {
+                        game.undo_last_swap();
+                    } else {
+                        // handle_click expects raw window coordinates, not
+                        // the already-converted world coordinates above.
+                        let (raw_x, raw_y) = mouse_position();
+                        game.handle_click(raw_x, raw_y);
+                    }
                 }
                 GameState::GameOver => {
-                    let screen_width = screen_width();
-                    let screen_height = screen_height();
-                    let button_x = screen_width / 2.0 - 100.0;
-                    let button_y = screen_height / 2.0 + 120.0;
+                    let button_x = DESIGN_WIDTH / 2.0 - 100.0;
+                    let button_y = DESIGN_HEIGHT / 2.0 + 120.0;
 
                     if mouse_x >= button_x
                         && mouse_x <= button_x + 200.0
@@ -723,6 +1246,24 @@ async fn main() {
             }
         }
 
+        if game.state == GameState::Playing {
+            if is_key_pressed(KeyCode::Up) {
+                game.move_cursor(-1, 0);
+            }
+            if is_key_pressed(KeyCode::Down) {
+                game.move_cursor(1, 0);
+            }
+            if is_key_pressed(KeyCode::Left) {
+                game.move_cursor(0, -1);
+            }
+            if is_key_pressed(KeyCode::Right) {
+                game.move_cursor(0, 1);
+            }
+            if is_key_pressed(KeyCode::Space) || is_key_pressed(KeyCode::Enter) {
+                game.confirm_cursor();
+            }
+        }
+
         game.draw();
         next_frame().await;
     }
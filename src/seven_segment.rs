@@ -0,0 +1,60 @@
+//! Retro seven-segment LED digit rendering, used by the HUD for the
+//! countdown timer and scores instead of plain text.
+use macroquad::prelude::*;
+
+/// Lit-segment bitmask per digit, in the conventional a/b/c/d/e/f/g order
+/// (a = top, b = top-right, c = bottom-right, d = bottom, e = bottom-left,
+/// f = top-left, g = middle).
+const SEGMENTS: [[bool; 7]; 10] = [
+    [true, true, true, true, true, true, false],    // 0
+    [false, true, true, false, false, false, false], // 1
+    [true, true, false, true, true, false, true],   // 2
+    [true, true, true, true, false, false, true],   // 3
+    [false, true, true, false, false, true, true],  // 4
+    [true, false, true, true, false, true, true],   // 5
+    [true, false, true, true, true, true, true],    // 6
+    [true, true, true, false, false, false, false], // 7
+    [true, true, true, true, true, true, true],     // 8
+    [true, true, true, true, false, true, true],    // 9
+];
+
+/// Draws one digit's seven segments as filled quads at `(x, y)`, `size`
+/// pixels tall, lighting up the segments `digit` requires and dimming the
+/// rest so the unlit tube pattern behind the glyph is still visible.
+fn draw_digit(digit: u32, x: f32, y: f32, size: f32, lit_color: Color, dim_color: Color) {
+    let thickness = size * 0.18;
+    let width = size * 0.55;
+    let half = size / 2.0;
+    let lit = SEGMENTS[digit as usize % 10];
+    let color = |i: usize| if lit[i] { lit_color } else { dim_color };
+
+    draw_rectangle(x + thickness / 2.0, y, width - thickness, thickness, color(0)); // a
+    draw_rectangle(x + width - thickness, y + thickness / 2.0, thickness, half - thickness, color(1)); // b
+    draw_rectangle(x + width - thickness, y + half + thickness / 2.0, thickness, half - thickness, color(2)); // c
+    draw_rectangle(x + thickness / 2.0, y + size - thickness, width - thickness, thickness, color(3)); // d
+    draw_rectangle(x, y + half + thickness / 2.0, thickness, half - thickness, color(4)); // e
+    draw_rectangle(x, y + thickness / 2.0, thickness, half - thickness, color(5)); // f
+    draw_rectangle(x + thickness / 2.0, y + half - thickness / 2.0, width - thickness, thickness, color(6)); // g
+}
+
+/// Renders `value` as `digits` seven-segment characters, right-aligned and
+/// zero-padded, starting at `(x, y)` with each digit `size` pixels tall.
+pub fn draw_seven_segment(
+    value: u32,
+    digits: usize,
+    x: f32,
+    y: f32,
+    size: f32,
+    lit_color: Color,
+    dim_color: Color,
+) {
+    let text = format!("{:0width$}", value, width = digits);
+    let digit_width = size * 0.55;
+    let spacing = size * 0.2;
+
+    for (i, ch) in text.chars().enumerate() {
+        let digit = ch.to_digit(10).unwrap_or(0);
+        let dx = x + i as f32 * (digit_width + spacing);
+        draw_digit(digit, dx, y, size, lit_color, dim_color);
+    }
+}